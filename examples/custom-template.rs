@@ -11,8 +11,10 @@ fn main() -> Result<()> {
     // run command execute upversion check in the background and finish immediately.
     version_context.run("0.0.1")?;
 
-    // sleep here simulator your program
-    std::thread::sleep(std::time::Duration::from_secs(3));
+    // your program logic here
+
+    // wait until the check is done, up to the given timeout
+    version_context.wait(std::time::Duration::from_secs(3));
 
     // at the end of your program, you can call printstd_with_template to print to the STDOUT a alert information for a new version which released
     version_context.printstd_with_template(CUSTOM_TEMPLATE);
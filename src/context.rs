@@ -1,27 +1,42 @@
 //! asdasd
-use crate::data::{NewerReleaseVersion, Vendor};
+use crate::data::{CheckStatus, NewerReleaseVersion, RateLimited, Release, Vendor};
+use crate::policy::{self, SkipReason};
+use crate::state::{self, State};
 use crate::template::new_version_available;
 use anyhow::anyhow;
 use anyhow::Result as AnyResult;
 use curl::easy::Easy;
 use semver::Version;
-use std::collections::HashMap;
 use std::env;
-use std::sync::{Arc, Mutex};
-use std::time::Duration;
-use tokio::runtime::{Builder, Runtime};
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, UNIX_EPOCH};
+#[cfg(feature = "tokio")]
+use tokio::runtime::Handle;
+
+/// Check status shared with the background task, the condvar is notified on every status change
+type SharedStatus = Arc<(Mutex<CheckStatus>, Condvar)>;
+
 /// holds the vendor type and the base version context
 pub struct CheckVersion {
     client: Arc<Mutex<Easy>>,
-    runtime: Runtime,
-    vendor: Arc<Mutex<Box<dyn Vendor + Send>>>,
+    /// Runtime to run the background check on, a background thread is used when not given
+    #[cfg(feature = "tokio")]
+    handle: Option<Handle>,
+    vendors: Arc<Mutex<Vec<Box<dyn Vendor + Send>>>>,
     app_name: String,
-    result: Arc<Mutex<HashMap<String, NewerReleaseVersion>>>,
+    status: SharedStatus,
+    state_path: Option<PathBuf>,
+    check_interval: Option<Duration>,
+    skip_reason: Option<SkipReason>,
+    terminal_only: bool,
 }
 
 /// Default message template when newer version is detected
 static DEFAULT_TEMPLATE: &str = r#"
-==> 🙆‍♂️ Newer {{ app_name }} version available: {{ new_version }} (currently running: {{ current_version }}) {% if download_link %}| Link: {{ download_link }} {% endif %}
+==> 🙆‍♂️ Newer {{ app_name }} version available: {{ new_version }} (currently running: {{ current_version }}) {% if source %}(from {{ source }}) {% endif %}{% if download_link %}| Link: {{ download_link }} {% elif install_command %}| Install: {{ install_command }} {% endif %}
 "#;
 
 impl CheckVersion {
@@ -29,8 +44,44 @@ impl CheckVersion {
     ///
     /// # Errors
     ///
-    /// Will return `Err` if runtime multi thread could not be build
+    /// Will return `Err` if the HTTP client could not be configured
     pub fn new(app_name: &str, vendor: Box<dyn Vendor + Send>, timeout: u64) -> AnyResult<Self> {
+        Self::with_fallback(app_name, vec![vendor], timeout)
+    }
+
+    /// Create a new check version instance with an ordered list of vendors.
+    ///
+    /// The vendors are queried by order, and the next vendor is used only when the previous one returns an
+    /// error (network error, rate limit, unexpected response).
+    ///
+    /// ## Usage Example
+    /// ```
+    /// use anyhow::Result;
+    /// use upversion::vendors::{Api, GitHubVendor};
+    /// use upversion::CheckVersion;
+    ///
+    /// fn main() -> Result<()> {
+    ///     let github = Box::new(GitHubVendor::new("owner", "repo"));
+    ///     let mirror = Box::new(Api::new("http://127.0.0.1:3000"));
+    ///     let version_context = CheckVersion::with_fallback("app-name", vec![github, mirror], 2)?;
+    ///
+    ///     version_context.run("0.0.1")?;
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the HTTP client could not be configured or vendors list is empty
+    pub fn with_fallback(
+        app_name: &str,
+        vendors: Vec<Box<dyn Vendor + Send>>,
+        timeout: u64,
+    ) -> AnyResult<Self> {
+        if vendors.is_empty() {
+            return Err(anyhow!("at least one vendor is required"));
+        }
+
         let easy = {
             let mut easy = Easy::new();
             easy.timeout(Duration::from_secs(timeout))?;
@@ -40,107 +91,401 @@ impl CheckVersion {
 
         Ok(Self {
             client: Arc::new(Mutex::new(easy)),
-            runtime: Builder::new_multi_thread()
-                .worker_threads(1)
-                .enable_all()
-                .build()?,
-            vendor: Arc::new(Mutex::new(vendor)),
+            #[cfg(feature = "tokio")]
+            handle: None,
+            vendors: Arc::new(Mutex::new(vendors)),
             app_name: app_name.to_string(),
-            result: Arc::new(Mutex::new(HashMap::new())),
+            status: Arc::new((Mutex::new(CheckStatus::Pending), Condvar::new())),
+            state_path: State::default_path(app_name),
+            check_interval: None,
+            skip_reason: policy::skip_reason(app_name),
+            terminal_only: true,
         })
     }
 
-    /// Run version check in the background
+    /// Override the state file location.
+    ///
+    /// The state file keeps details between runs (like rate limit reset time), by default the file is stored
+    /// in the platform cache directory: `<cache dir>/upversion/<app_name>.json`. The file is written only when
+    /// the state changed.
+    pub fn with_state_path(mut self, path: &Path) -> Self {
+        self.state_path = Some(path.to_path_buf());
+        self
+    }
+
+    /// Don't read or write the state file. Rate limits, conditional requests and the check interval are not
+    /// kept between runs.
+    pub fn without_state(mut self) -> Self {
+        self.state_path = None;
+        self
+    }
+
+    /// Check for a new version at most once per interval.
+    ///
+    /// The last successful check time and release are saved in the state file, and when [`CheckVersion::run`]
+    /// is called within the interval the result is taken from the saved release without network request.
+    ///
+    /// ## Usage Example
+    /// ```
+    /// use anyhow::Result;
+    /// use upversion::vendors::GitHubVendor;
+    /// use upversion::CheckVersion;
+    /// use std::time::Duration;
+    ///
+    /// fn main() -> Result<()> {
+    ///     let github = Box::new(GitHubVendor::new("owner", "repo"));
+    ///     let version_context = CheckVersion::new("app-name", github, 2)?
+    ///         .with_check_interval(Duration::from_secs(24 * 60 * 60));
+    ///
+    ///     version_context.run("0.0.1")?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn with_check_interval(mut self, interval: Duration) -> Self {
+        self.check_interval = Some(interval);
+        self
+    }
+
+    /// Enable or disable the environment policy (enabled by default).
+    ///
+    /// When enabled, the check is skipped if one of `NO_UPDATE_NOTIFIER`, `DO_NOT_TRACK` or
+    /// `<APP_NAME>_NO_UPDATE_CHECK` environment variables is set, or when running in CI (`CI`, `GITHUB_ACTIONS`,
+    /// `BUILDKITE`, ...). Variables set to empty, `0` or `false` are ignored.
+    pub fn with_env_policy(mut self, enabled: bool) -> Self {
+        self.skip_reason = if enabled {
+            policy::skip_reason(&self.app_name)
+        } else {
+            None
+        };
+        self
+    }
+
+    /// Print only when the output is attached to a terminal (enabled by default).
+    ///
+    /// When the output is piped (`app | jq`) the notification is not printed, to keep the output machine readable.
+    /// Disable to print regardless of the output type.
+    pub fn with_terminal_only(mut self, enabled: bool) -> Self {
+        self.terminal_only = enabled;
+        self
+    }
+
+    /// Return the reason the check is skipped, or `None` when the check is performed
+    ///
+    /// ## Usage Example
+    /// ```
+    /// use anyhow::Result;
+    /// use upversion::vendors::GitHubVendor;
+    /// use upversion::CheckVersion;
+    ///
+    /// fn main() -> Result<()> {
+    ///     let github = Box::new(GitHubVendor::new("owner", "repo"));
+    ///     let version_context = CheckVersion::new("app-name", github, 2)?;
+    ///
+    ///     if let Some(reason) = version_context.skip_reason() {
+    ///         println!("version check skipped: {}", reason);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn skip_reason(&self) -> Option<&SkipReason> {
+        self.skip_reason.as_ref()
+    }
+
+    /// Run the background check on the blocking threads pool of the given tokio runtime, instead of a
+    /// dedicated background thread.
+    ///
+    /// ## Usage Example
+    /// ```
+    /// use anyhow::Result;
+    /// use upversion::vendors::GitHubVendor;
+    /// use upversion::CheckVersion;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<()> {
+    ///     let github = Box::new(GitHubVendor::new("owner", "repo"));
+    ///     let version_context = CheckVersion::new("app-name", github, 2)?
+    ///         .with_handle(tokio::runtime::Handle::current());
+    ///
+    ///     version_context.run("0.0.1")?;
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub fn with_handle(mut self, handle: Handle) -> Self {
+        self.handle = Some(handle);
+        self
+    }
+
+    /// Run version check in the background.
+    ///
+    /// The check runs on a background thread (or on the runtime given with `with_handle`), and does not block
+    /// the process exit.
     ///
     /// # Errors
     ///
-    /// Will return `Err` if runtime multi thread could not be build
+    /// Will return `Err` if the version is invalid or the background thread could not be spawned
     pub fn run(&self, version: &str) -> AnyResult<()> {
-        let version = match Self::parse_version(version) {
-            Ok(v) => v,
+        let version = Self::parse_current_version(version)?;
+        if let Some(status) = self.precheck(&version) {
+            Self::set_status(&self.status, status);
+            return Ok(());
+        }
+
+        Self::set_status(&self.status, CheckStatus::Pending);
+
+        let status = self.status.clone();
+        let task = self.task(version);
+        let job = move || Self::set_status(&status, task());
+
+        #[cfg(feature = "tokio")]
+        if let Some(handle) = &self.handle {
+            handle.spawn_blocking(job);
+            return Ok(());
+        }
+
+        thread::Builder::new()
+            .name("upversion".to_string())
+            .spawn(job)?;
+        Ok(())
+    }
+
+    /// Check for a new version on the current tokio runtime.
+    ///
+    /// The vendors request is blocking, and performed on the runtime blocking threads pool.
+    ///
+    /// ## Usage Example
+    /// ```
+    /// use anyhow::Result;
+    /// use upversion::vendors::GitHubVendor;
+    /// use upversion::{CheckStatus, CheckVersion};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<()> {
+    ///     let github = Box::new(GitHubVendor::new("owner", "repo"));
+    ///     let version_context = CheckVersion::new("app-name", github, 2)?;
+    ///
+    ///     if let CheckStatus::NewerAvailable(release) = version_context.check("0.0.1").await? {
+    ///         println!("update to {}", release.new_version);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the version is invalid or the check task panicked
+    #[cfg(feature = "tokio")]
+    pub async fn check(&self, version: &str) -> AnyResult<CheckStatus> {
+        let version = Self::parse_current_version(version)?;
+        let status = match self.precheck(&version) {
+            Some(status) => status,
+            None => {
+                Self::set_status(&self.status, CheckStatus::Pending);
+                tokio::task::spawn_blocking(self.task(version)).await?
+            }
+        };
+        Self::set_status(&self.status, status.clone());
+        Ok(status)
+    }
+
+    fn parse_current_version(version: &str) -> AnyResult<Version> {
+        match Self::parse_version(version) {
+            Ok(v) => Ok(v),
             Err(e) => {
                 log::debug!("invalid version: {}. err: {:?}", version, e);
-                return Err(anyhow!("unsupported version: {}. err: {:?}", version, e));
+                Err(anyhow!("unsupported version: {}. err: {:?}", version, e))
             }
-        };
-        let res = self.result.clone();
-        let vendor = self.vendor.clone();
-        let client = self.client.clone();
+        }
+    }
 
-        self.runtime.spawn(async move {
-            let mut r = match res.lock() {
-                Ok(r) => r,
-                Err(e) => {
-                    log::debug!("cloud not lock result. err: {:?}", e);
-                    return;
-                }
-            };
+    /// Return the status without querying the vendors, when the check is skipped by the environment policy
+    /// or the last check is in the check interval
+    fn precheck(&self, version: &Version) -> Option<CheckStatus> {
+        if let Some(reason) = &self.skip_reason {
+            log::debug!("version check skipped: {}", reason);
+            return Some(CheckStatus::Skipped(reason.clone()));
+        }
 
-            let vendor_mutex = match vendor.lock() {
-                Ok(v) => v,
-                Err(e) => {
-                    log::debug!("cloud not lock vendor. err:: {:?}", e);
-                    return;
-                }
-            };
+        if let (Some(interval), Some(path)) = (self.check_interval, &self.state_path) {
+            if let Some(release) = State::load(path).recent_release(interval, state::now()) {
+                log::debug!("last check is in the interval, using the cached release");
+                return Some(Self::release_status(version, release));
+            }
+        }
+        None
+    }
 
-            let client = match client.lock() {
+    /// Return the blocking check task, that query the vendors and update the state file
+    fn task(&self, version: Version) -> impl FnOnce() -> CheckStatus + Send + 'static {
+        let vendors = self.vendors.clone();
+        let client = self.client.clone();
+        let state_path = self.state_path.clone();
+        let check_interval = self.check_interval;
+
+        move || {
+            let vendors = match vendors.lock() {
                 Ok(v) => v,
                 Err(e) => {
                     log::debug!("cloud not lock vendor. err:: {:?}", e);
-                    return;
+                    return CheckStatus::Failed(format!("could not lock vendor. err: {:?}", e));
                 }
             };
 
-            let release = match vendor_mutex.get(client) {
-                Ok(r) => r,
-                Err(e) => {
-                    log::debug!("could not get release details. err: {:?}", e);
-                    return;
-                }
+            let mut state = match &state_path {
+                Some(path) => State::load(path),
+                None => State::default(),
             };
 
-            let release_version = if release.version.starts_with('v') {
-                release.version.trim_start_matches('v').to_string()
-            } else {
-                release.version
-            };
+            let loaded = state.clone();
+            let release = Self::get_release(&vendors, &client, &mut state);
+            if let (Ok(release), Some(_)) = (&release, check_interval) {
+                state.last_check = Some(state::now());
+                state.last_release = Some(release.clone());
+            }
 
-            let release_version = match Self::parse_version(release_version.as_ref()) {
-                Ok(v) => v,
+            if let Some(path) = &state_path {
+                if state != loaded {
+                    if let Err(e) = state.save(path) {
+                        log::debug!("could not save state file {:?}. err: {:?}", path, e);
+                    }
+                }
+            }
+
+            match release {
+                Ok(release) => Self::release_status(&version, release),
                 Err(e) => {
-                    log::debug!("invalid release version: {}. err: {:?}", release_version, e);
-                    return;
+                    log::debug!("could not get release details. err: {:?}", e);
+                    match e.downcast::<RateLimited>() {
+                        Ok(rate_limited) => CheckStatus::RateLimited(rate_limited),
+                        Err(e) => CheckStatus::Failed(e.to_string()),
+                    }
                 }
-            };
+            }
+        }
+    }
 
-            if version >= release_version {
-                log::debug!(
-                    "newer version not found. current version: {:?} latest version: {:?}",
-                    version,
+    /// Return the check status of the given release, compared to the current version
+    fn release_status(version: &Version, release: Release) -> CheckStatus {
+        let release_version = if release.version.starts_with('v') {
+            release.version.trim_start_matches('v').to_string()
+        } else {
+            release.version
+        };
+
+        let release_version = match Self::parse_version(release_version.as_ref()) {
+            Ok(v) => v,
+            Err(e) => {
+                log::debug!("invalid release version: {}. err: {:?}", release_version, e);
+                return CheckStatus::Failed(format!(
+                    "invalid release version: {}",
                     release_version
-                );
-                return;
+                ));
             }
+        };
 
-            r.insert(
-                "result".to_string(),
-                NewerReleaseVersion {
-                    current_version: version,
-                    new_version: release_version,
-                    release_url: Self::extract_release_link(&release.downloads_releases),
-                },
+        if *version >= release_version {
+            log::debug!(
+                "newer version not found. current version: {:?} latest version: {:?}",
+                version,
+                release_version
             );
-        });
+            return CheckStatus::UpToDate;
+        }
 
-        Ok(())
+        CheckStatus::NewerAvailable(NewerReleaseVersion {
+            current_version: version.clone(),
+            new_version: release_version,
+            release_url: release
+                .download_link
+                .or_else(|| Self::extract_release_link(&release.downloads_releases)),
+            install_command: release.install_command,
+            source: release.source,
+        })
     }
 
-    pub fn printstd(&self) {
-        match self.render(DEFAULT_TEMPLATE) {
-            Ok(r) => println!("{}", r),
-            Err(r) => log::debug!("render error {:?}", r),
+    fn set_status(status: &(Mutex<CheckStatus>, Condvar), new_status: CheckStatus) {
+        let (lock, changed) = status;
+        match lock.lock() {
+            Ok(mut s) => *s = new_status,
+            Err(e) => log::debug!("cloud not lock status. err: {:?}", e),
+        }
+        changed.notify_all();
+    }
+
+    /// Block until the background check is done or the timeout passed, and return the status.
+    ///
+    /// Use it at the end of short-lived commands instead of sleeping. When the timeout passed the status is
+    /// [`CheckStatus::Pending`]. Calling `wait` before [`CheckVersion::run`] blocks until the timeout.
+    ///
+    /// ## Usage Example
+    /// ```
+    /// use anyhow::Result;
+    /// use std::time::Duration;
+    /// use upversion::vendors::GitHubVendor;
+    /// use upversion::CheckVersion;
+    ///
+    /// fn main() -> Result<()> {
+    ///     let github = Box::new(GitHubVendor::new("owner", "repo"));
+    ///     let version_context = CheckVersion::new("app-name", github, 2)?;
+    ///     version_context.run("0.0.1")?;
+    ///
+    ///     let status = version_context.wait(Duration::from_secs(3));
+    ///     println!("version check status: {:?}", status);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn wait(&self, timeout: Duration) -> CheckStatus {
+        let (lock, changed) = &*self.status;
+        let status = match lock.lock() {
+            Ok(s) => s,
+            Err(e) => return CheckStatus::Failed(format!("could not lock status. err: {:?}", e)),
         };
+        match changed.wait_timeout_while(status, timeout, |s| *s == CheckStatus::Pending) {
+            Ok((s, _)) => s.clone(),
+            Err(e) => CheckStatus::Failed(format!("could not lock status. err: {:?}", e)),
+        }
+    }
+
+    /// Return the status of the last [`CheckVersion::run`]
+    ///
+    /// ## Usage Example
+    /// ```
+    /// use anyhow::Result;
+    /// use upversion::vendors::GitHubVendor;
+    /// use upversion::{CheckStatus, CheckVersion};
+    ///
+    /// fn main() -> Result<()> {
+    ///     let github = Box::new(GitHubVendor::new("owner", "repo"));
+    ///     let version_context = CheckVersion::new("app-name", github, 2)?;
+    ///     version_context.run("0.0.1")?;
+    ///
+    ///     match version_context.status() {
+    ///         CheckStatus::NewerAvailable(release) => println!("update to {}", release.new_version),
+    ///         CheckStatus::RateLimited(rate_limited) => println!("version check {}", rate_limited),
+    ///         CheckStatus::Failed(reason) => println!("version check failed: {}", reason),
+    ///         _ => {}
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn status(&self) -> CheckStatus {
+        match self.status.0.lock() {
+            Ok(s) => s.clone(),
+            Err(e) => CheckStatus::Failed(format!("could not lock status. err: {:?}", e)),
+        }
+    }
+
+    /// Return the newer release details, when the check is done and newer version is available
+    pub fn result(&self) -> Option<NewerReleaseVersion> {
+        match self.status() {
+            CheckStatus::NewerAvailable(r) => Some(r),
+            _ => None,
+        }
+    }
+
+    /// Print the default notification to the STDOUT, when newer version is available and STDOUT is a terminal
+    pub fn printstd(&self) {
+        self.printstd_with_template(DEFAULT_TEMPLATE);
     }
 
     /// create custom template
@@ -151,26 +496,116 @@ impl CheckVersion {
     /// - `{{ new_version }}`: Newest version number
     /// - `{{ current_version }}`: Current version
     /// - `{{ download_link }}`: Link to the new release file
+    /// - `{{ install_command }}`: Command that installs the new release (for package registries vendors)
+    /// - `{{ source }}`: Name of the source the new release was taken from (for `AggregateVendor`)
+    ///
+    /// The notification is printed only when STDOUT is a terminal, see [`CheckVersion::with_terminal_only`].
     pub fn printstd_with_template(&self, template: &str) {
-        match self.render(template) {
-            Ok(r) => println!("{}", r),
-            Err(r) => log::debug!("render error {:?}", r),
-        };
+        let mut stdout = io::stdout();
+        let is_terminal = stdout.is_terminal();
+        if let Err(e) = self.write_to_terminal(&mut stdout, is_terminal, template) {
+            log::debug!("render error {:?}", e);
+        }
     }
 
-    fn render(&self, template: &str) -> AnyResult<String> {
-        let r = self.result.lock();
-        let newer_release_version = match r {
-            Ok(ref r) => match r.get("result") {
-                Some(v) => v,
-                None => {
-                    log::debug!("result is empty");
-                    return Err(anyhow!("result not found"));
-                }
-            },
-            Err(e) => {
-                log::debug!("lock error: {:?}", e);
-                return Err(anyhow!("lock error: {:?}", e));
+    /// Print the default notification to the STDERR, when newer version is available and STDERR is a terminal
+    ///
+    /// Return true when the notification was printed.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the template could not be rendered or written
+    pub fn eprint(&self) -> AnyResult<bool> {
+        self.eprint_with_template(DEFAULT_TEMPLATE)
+    }
+
+    /// Print custom template to the STDERR, see [`CheckVersion::printstd_with_template`] for the supported fields.
+    ///
+    /// Return true when the notification was printed.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the template could not be rendered or written
+    pub fn eprint_with_template(&self, template: &str) -> AnyResult<bool> {
+        let mut stderr = io::stderr();
+        let is_terminal = stderr.is_terminal();
+        self.write_to_terminal(&mut stderr, is_terminal, template)
+    }
+
+    /// Write the default notification to the given writer, when newer version is available.
+    ///
+    /// Return true when the notification was written.
+    ///
+    /// ## Usage Example
+    /// ```
+    /// use anyhow::Result;
+    /// use upversion::vendors::GitHubVendor;
+    /// use upversion::CheckVersion;
+    ///
+    /// fn main() -> Result<()> {
+    ///     let github = Box::new(GitHubVendor::new("owner", "repo"));
+    ///     let version_context = CheckVersion::new("app-name", github, 2)?;
+    ///     version_context.run("0.0.1")?;
+    ///
+    ///     let mut output = vec![];
+    ///     if version_context.write_to(&mut output)? {
+    ///         eprint!("{}", String::from_utf8_lossy(&output));
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the template could not be rendered or written
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> AnyResult<bool> {
+        self.write_to_with_template(writer, DEFAULT_TEMPLATE)
+    }
+
+    /// Write custom template to the given writer, see [`CheckVersion::printstd_with_template`] for the supported
+    /// fields.
+    ///
+    /// Return true when the notification was written.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the template could not be rendered or written
+    pub fn write_to_with_template<W: Write>(
+        &self,
+        writer: &mut W,
+        template: &str,
+    ) -> AnyResult<bool> {
+        match self.render(template)? {
+            Some(r) => {
+                writeln!(writer, "{}", r)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Write the template to the given output stream, unless printing is limited to terminal and the stream is
+    /// not a terminal
+    fn write_to_terminal<W: Write>(
+        &self,
+        writer: &mut W,
+        is_terminal: bool,
+        template: &str,
+    ) -> AnyResult<bool> {
+        if self.terminal_only && !is_terminal {
+            log::debug!("output is not a terminal, skip printing");
+            return Ok(false);
+        }
+        self.write_to_with_template(writer, template)
+    }
+
+    /// Render the template, `None` is returned when newer version is not available
+    fn render(&self, template: &str) -> AnyResult<Option<String>> {
+        let newer_release_version = match self.result() {
+            Some(r) => r,
+            None => {
+                log::debug!("newer version is not available");
+                return Ok(None);
             }
         };
 
@@ -179,15 +614,91 @@ impl CheckVersion {
             self.app_name.as_ref(),
             &newer_release_version.new_version,
             &newer_release_version.current_version,
-            newer_release_version.release_url.clone(),
+            newer_release_version.release_url,
+            newer_release_version.install_command,
+            newer_release_version.source,
         )
+        .map(Some)
+    }
+
+    /// Get the release from the first vendor that succeed.
+    ///
+    /// Vendors that are rate limited (by the state) are skipped, and a vendor that returns [`RateLimited`]
+    /// error is recorded in the state until the reset time. When all the vendors are rate limited, the
+    /// [`RateLimited`] error with the earliest reset time is returned. The release of vendors that support
+    /// conditional requests is kept in the state, and reused when the source was not modified.
+    fn get_release(
+        vendors: &[Box<dyn Vendor + Send>],
+        client: &Mutex<Easy>,
+        state: &mut State,
+    ) -> AnyResult<Release> {
+        let now = state::now();
+
+        let mut errors = vec![];
+        // earliest reset time, `None` when any vendor failed without rate limit
+        let mut rate_limited_until = Some(u64::MAX);
+        for (index, vendor) in vendors.iter().enumerate() {
+            let id = vendor.id();
+            if let Some(until) = state.rate_limited(&id, now) {
+                log::debug!(
+                    "vendor {} ({}) skipped, rate limited until {}",
+                    index,
+                    id,
+                    until
+                );
+                errors.push(format!("vendor {}: rate limited until {}", index, until));
+                rate_limited_until = rate_limited_until.map(|earliest| earliest.min(until));
+                continue;
+            }
+
+            let client = match client.lock() {
+                Ok(c) => c,
+                Err(e) => return Err(anyhow!("could not lock client. err: {:?}", e)),
+            };
+
+            let cached = state.releases.get(&id).cloned();
+            match vendor.get_conditional(client, cached.as_ref()) {
+                Ok(cached) => {
+                    state.rate_limited_until.remove(&id);
+                    if cached.validators.is_empty() {
+                        state.releases.remove(&id);
+                    } else {
+                        state.releases.insert(id, cached.clone());
+                    }
+                    return Ok(cached.release);
+                }
+                Err(e) => {
+                    log::debug!("vendor {} ({}) failed. err: {:?}", index, id, e);
+                    match e.downcast_ref::<RateLimited>() {
+                        Some(rate_limited) => {
+                            let until = rate_limited.until_secs();
+                            state.rate_limited_until.insert(id, until);
+                            rate_limited_until =
+                                rate_limited_until.map(|earliest| earliest.min(until));
+                        }
+                        None => {
+                            state.rate_limited_until.remove(&id);
+                            rate_limited_until = None;
+                        }
+                    }
+                    errors.push(format!("vendor {}: {}", index, e));
+                }
+            }
+        }
+        match rate_limited_until {
+            Some(until) if !vendors.is_empty() => Err(RateLimited {
+                until: UNIX_EPOCH + Duration::from_secs(until),
+            }
+            .into()),
+            _ => Err(anyhow!("all vendors failed. errors: {:?}", errors)),
+        }
     }
 
     /// parse text version to Version struct
     fn parse_version(version: &str) -> AnyResult<Version> {
         match Version::parse(version) {
             Ok(v) => Ok(v),
-            Err(e) => return Err(anyhow!("invalid version: {}. err:; {}", version, e)),
+            Err(e) => Err(anyhow!("invalid version: {}. err:; {}", version, e)),
         }
     }
 
@@ -233,3 +744,334 @@ impl CheckVersion {
         Some(find[0].clone())
     }
 }
+
+#[cfg(test)]
+mod test_context {
+    use super::*;
+    use insta::assert_debug_snapshot;
+    use std::sync::MutexGuard;
+    use std::time::UNIX_EPOCH;
+
+    struct FailVendor;
+
+    impl Vendor for FailVendor {
+        fn id(&self) -> String {
+            "fail".to_string()
+        }
+
+        fn get(&self, _client: MutexGuard<Easy>) -> AnyResult<Release> {
+            Err(anyhow!("rate limit exceeded"))
+        }
+    }
+
+    struct RateLimitedVendor;
+
+    impl Vendor for RateLimitedVendor {
+        fn id(&self) -> String {
+            "rate-limited".to_string()
+        }
+
+        fn get(&self, _client: MutexGuard<Easy>) -> AnyResult<Release> {
+            Err(RateLimited {
+                until: UNIX_EPOCH + Duration::from_secs(4_102_444_800),
+            }
+            .into())
+        }
+    }
+
+    struct StaticVendor(&'static str);
+
+    impl Vendor for StaticVendor {
+        fn id(&self) -> String {
+            format!("static:{}", self.0)
+        }
+
+        fn get(&self, _client: MutexGuard<Easy>) -> AnyResult<Release> {
+            Ok(Release {
+                version: self.0.to_string(),
+                downloads_releases: vec![],
+                download_link: None,
+                install_command: None,
+                source: None,
+            })
+        }
+    }
+
+    #[test]
+    fn can_get_release_from_fallback_vendor() {
+        let vendors: Vec<Box<dyn Vendor + Send>> = vec![
+            Box::new(FailVendor),
+            Box::new(StaticVendor("1.0.0")),
+            Box::new(StaticVendor("2.0.0")),
+        ];
+        assert_debug_snapshot!(CheckVersion::get_release(
+            &vendors,
+            &Mutex::new(Easy::new()),
+            &mut State::default()
+        ));
+    }
+
+    #[test]
+    fn can_get_release_when_all_vendors_failed() {
+        let vendors: Vec<Box<dyn Vendor + Send>> = vec![Box::new(FailVendor), Box::new(FailVendor)];
+        assert_debug_snapshot!(CheckVersion::get_release(
+            &vendors,
+            &Mutex::new(Easy::new()),
+            &mut State::default()
+        ));
+    }
+
+    #[test]
+    fn can_skip_rate_limited_vendor() {
+        let vendors: Vec<Box<dyn Vendor + Send>> =
+            vec![Box::new(RateLimitedVendor), Box::new(StaticVendor("1.0.0"))];
+        let mut state = State::default();
+        assert_debug_snapshot!(CheckVersion::get_release(
+            &vendors,
+            &Mutex::new(Easy::new()),
+            &mut state
+        ));
+        assert_debug_snapshot!(state);
+
+        // the rate limit is kept by vendor id, reordered vendors are not affected
+        let vendors: Vec<Box<dyn Vendor + Send>> =
+            vec![Box::new(StaticVendor("2.0.0")), Box::new(RateLimitedVendor)];
+        assert_debug_snapshot!(CheckVersion::get_release(
+            &vendors,
+            &Mutex::new(Easy::new()),
+            &mut state
+        ));
+
+        // the rate limited vendor is not queried again before the reset time
+        let vendors: Vec<Box<dyn Vendor + Send>> = vec![Box::new(RateLimitedVendor)];
+        let err =
+            CheckVersion::get_release(&vendors, &Mutex::new(Easy::new()), &mut state).unwrap_err();
+        assert_debug_snapshot!(err.downcast_ref::<RateLimited>().map(|r| r.until_secs()));
+    }
+
+    #[test]
+    fn can_get_rate_limited_status() {
+        let version_context = CheckVersion::with_fallback(
+            "app-name",
+            vec![Box::new(RateLimitedVendor), Box::new(RateLimitedVendor)],
+            1,
+        )
+        .unwrap()
+        .with_env_policy(false)
+        .without_state();
+        version_context.run("1.0.0").unwrap();
+        match version_context.wait(Duration::from_secs(5)) {
+            CheckStatus::RateLimited(rate_limited) => {
+                assert_eq!(rate_limited.until_secs(), 4_102_444_800);
+            }
+            status => panic!("unexpected status: {:?}", status),
+        }
+    }
+
+    #[test]
+    fn can_use_cached_release_in_check_interval() {
+        let path = env::temp_dir()
+            .join(format!("upversion-context-test-{}", std::process::id()))
+            .join("check-interval.json");
+
+        let state = State {
+            last_check: Some(state::now()),
+            last_release: Some(Release {
+                version: "v2.0.0".to_string(),
+                downloads_releases: vec![],
+                download_link: Some("https://foo.test/app-2.0.0".to_string()),
+                install_command: None,
+                source: None,
+            }),
+            ..State::default()
+        };
+        state.save(&path).unwrap();
+
+        let version_context = CheckVersion::new("app-name", Box::new(FailVendor), 1)
+            .unwrap()
+            .with_state_path(&path)
+            .with_check_interval(Duration::from_secs(60))
+            .with_env_policy(false);
+        version_context.run("1.0.0").unwrap();
+
+        assert_debug_snapshot!(version_context.render(DEFAULT_TEMPLATE));
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn can_save_only_changed_state() {
+        let path = env::temp_dir()
+            .join(format!(
+                "upversion-context-state-test-{}",
+                std::process::id()
+            ))
+            .join("unchanged.json");
+
+        let version_context = CheckVersion::new("app-name", Box::new(StaticVendor("2.0.0")), 1)
+            .unwrap()
+            .with_state_path(&path)
+            .with_env_policy(false);
+        version_context.run("1.0.0").unwrap();
+        version_context.wait(Duration::from_secs(5));
+        assert!(!path.exists());
+
+        let version_context = CheckVersion::new("app-name", Box::new(StaticVendor("2.0.0")), 1)
+            .unwrap()
+            .with_state_path(&path)
+            .with_check_interval(Duration::from_secs(60))
+            .with_env_policy(false);
+        version_context.run("1.0.0").unwrap();
+        version_context.wait(Duration::from_secs(5));
+        assert!(State::load(&path).last_release.is_some());
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn can_print_only_to_terminal() {
+        let version_context = CheckVersion::new("app-name", Box::new(FailVendor), 1).unwrap();
+        CheckVersion::set_status(
+            &version_context.status,
+            CheckStatus::NewerAvailable(NewerReleaseVersion {
+                current_version: Version::parse("1.0.0").unwrap(),
+                new_version: Version::parse("1.1.0").unwrap(),
+                release_url: None,
+                install_command: None,
+                source: None,
+            }),
+        );
+
+        // piped output
+        let mut output = vec![];
+        assert!(!version_context
+            .write_to_terminal(&mut output, false, "{{ new_version }}")
+            .unwrap());
+        assert!(output.is_empty());
+
+        // terminal output
+        assert!(version_context
+            .write_to_terminal(&mut output, true, "{{ new_version }}")
+            .unwrap());
+        assert_eq!(String::from_utf8_lossy(&output), "1.1.0\n");
+
+        // piped output without terminal only limitation
+        let version_context = version_context.with_terminal_only(false);
+        let mut output = vec![];
+        assert!(version_context
+            .write_to_terminal(&mut output, false, "{{ new_version }}")
+            .unwrap());
+        assert_eq!(String::from_utf8_lossy(&output), "1.1.0\n");
+    }
+
+    #[test]
+    fn can_write_to() {
+        let version_context = CheckVersion::new("app-name", Box::new(FailVendor), 1).unwrap();
+
+        let mut output = vec![];
+        assert_debug_snapshot!(version_context.write_to(&mut output));
+
+        CheckVersion::set_status(
+            &version_context.status,
+            CheckStatus::NewerAvailable(NewerReleaseVersion {
+                current_version: Version::parse("1.0.0").unwrap(),
+                new_version: Version::parse("1.1.0").unwrap(),
+                release_url: None,
+                install_command: Some("cargo install app-name".to_string()),
+                source: None,
+            }),
+        );
+        assert_debug_snapshot!(version_context.write_to_with_template(&mut output, "{{ invalid"));
+        assert_debug_snapshot!(version_context.write_to(&mut output));
+        assert_debug_snapshot!(String::from_utf8(output));
+    }
+
+    #[test]
+    fn can_write_release_source() {
+        let version_context = CheckVersion::new("app-name", Box::new(FailVendor), 1).unwrap();
+        CheckVersion::set_status(
+            &version_context.status,
+            CheckStatus::NewerAvailable(NewerReleaseVersion {
+                current_version: Version::parse("1.0.0").unwrap(),
+                new_version: Version::parse("1.1.0").unwrap(),
+                release_url: None,
+                install_command: Some("cargo install app-name".to_string()),
+                source: Some("crates.io".to_string()),
+            }),
+        );
+
+        let mut output = vec![];
+        assert!(version_context.write_to(&mut output).unwrap());
+        assert_debug_snapshot!(String::from_utf8(output));
+    }
+
+    #[test]
+    fn can_get_release_status() {
+        let release = |version: &str| Release {
+            version: version.to_string(),
+            downloads_releases: vec![],
+            download_link: None,
+            install_command: None,
+            source: None,
+        };
+        let version = Version::parse("1.0.0").unwrap();
+
+        assert_debug_snapshot!(vec![
+            CheckVersion::release_status(&version, release("v1.1.0")),
+            CheckVersion::release_status(&version, release("1.0.0")),
+            CheckVersion::release_status(&version, release("latest")),
+        ]);
+    }
+
+    #[test]
+    fn can_get_skipped_status() {
+        let version_context = CheckVersion::new("app-name", Box::new(FailVendor), 1).unwrap();
+        assert_debug_snapshot!(version_context.status());
+
+        let version_context = CheckVersion {
+            skip_reason: Some(SkipReason::OptOut("NO_UPDATE_NOTIFIER".to_string())),
+            ..version_context
+        };
+        version_context.run("1.0.0").unwrap();
+        assert_debug_snapshot!(version_context.status());
+    }
+
+    #[test]
+    fn can_wait_for_check() {
+        let version_context = CheckVersion::new("app-name", Box::new(StaticVendor("v2.0.0")), 1)
+            .unwrap()
+            .with_env_policy(false)
+            .without_state();
+        version_context.run("1.0.0").unwrap();
+        assert_debug_snapshot!(version_context.wait(Duration::from_secs(5)));
+
+        let version_context = CheckVersion::new("app-name", Box::new(FailVendor), 1).unwrap();
+        assert_debug_snapshot!(version_context.wait(Duration::from_millis(10)));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn can_check_in_async_context() {
+        let version_context = CheckVersion::new("app-name", Box::new(StaticVendor("1.0.0")), 1)
+            .unwrap()
+            .with_env_policy(false)
+            .without_state();
+        assert_debug_snapshot!(version_context.check("1.0.0").await);
+        assert_debug_snapshot!(version_context.status());
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_run_on_runtime_handle() {
+        let version_context = CheckVersion::new("app-name", Box::new(FailVendor), 1)
+            .unwrap()
+            .with_env_policy(false)
+            .without_state()
+            .with_handle(Handle::current());
+        version_context.run("1.0.0").unwrap();
+        assert_debug_snapshot!(version_context.wait(Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn can_not_create_without_vendors() {
+        assert!(CheckVersion::with_fallback("app-name", vec![], 1).is_err());
+    }
+}
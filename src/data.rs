@@ -1,21 +1,121 @@
+use crate::policy::SkipReason;
 use anyhow::Result;
 use curl::easy::Easy;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 use std::sync::MutexGuard;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 pub trait Vendor {
+    /// Stable identifier of the vendor source (like the request URL), used to key the vendor details in the
+    /// state file. Must not include secrets, the state file is stored as plain text.
+    fn id(&self) -> String;
+
     fn get(&self, client: MutexGuard<Easy>) -> Result<Release>;
+
+    /// Get the release with a conditional request, based on the validators of the previous response.
+    ///
+    /// Vendors that support conditional requests return the previous release when the source was not modified,
+    /// and the validators of the new response otherwise. By default a regular request is performed.
+    fn get_conditional(
+        &self,
+        client: MutexGuard<Easy>,
+        _cached: Option<&CachedRelease>,
+    ) -> Result<CachedRelease> {
+        Ok(CachedRelease {
+            release: self.get(client)?,
+            validators: CacheValidators::default(),
+        })
+    }
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
 pub struct Release {
     pub version: String,
     pub downloads_releases: Vec<String>,
+    /// Download link of the running platform, when the vendor describe the platform assets explicitly.
+    /// Otherwise the link is extracted from `downloads_releases`.
+    #[serde(default)]
+    pub download_link: Option<String>,
+    /// Command that installs the release, for vendors that distribute a package instead of a download file
+    #[serde(default)]
+    pub install_command: Option<String>,
+    /// Name of the source the release was taken from, set by vendors that query several sources
+    #[serde(default)]
+    pub source: Option<String>,
 }
 
-#[derive(Clone, Debug)]
+/// Response validators for conditional requests (`If-None-Match` / `If-Modified-Since`)
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct CacheValidators {
+    #[serde(default)]
+    pub etag: Option<String>,
+    #[serde(default)]
+    pub last_modified: Option<String>,
+}
+
+impl CacheValidators {
+    pub fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+}
+
+/// Release with the validators of the response it was parsed from
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct CachedRelease {
+    pub release: Release,
+    pub validators: CacheValidators,
+}
+
+/// Newer release details
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct NewerReleaseVersion {
     pub current_version: semver::Version,
     pub new_version: semver::Version,
     pub release_url: Option<String>,
+    pub install_command: Option<String>,
+    pub source: Option<String>,
 }
+
+/// Status of the version check
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CheckStatus {
+    /// The check is not started or still running
+    Pending,
+    /// The check is skipped by the environment policy
+    Skipped(SkipReason),
+    /// The current version is the latest version
+    UpToDate,
+    /// Newer version is available
+    NewerAvailable(NewerReleaseVersion),
+    /// All the vendors are rate limited, the check is not done before the earliest reset time
+    RateLimited(RateLimited),
+    /// The check failed with the given reason
+    Failed(String),
+}
+
+/// Error returned by vendors when the source rate limit is exceeded.
+///
+/// `CheckVersion` does not query the vendor again before `until`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RateLimited {
+    pub until: SystemTime,
+}
+
+impl RateLimited {
+    /// Rate limit reset time as seconds since the unix epoch
+    pub fn until_secs(&self) -> u64 {
+        self.until
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default()
+    }
+}
+
+impl fmt::Display for RateLimited {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "rate limited until {} (unix time)", self.until_secs())
+    }
+}
+
+impl std::error::Error for RateLimited {}
@@ -10,6 +10,17 @@
 //!
 //! ## Vendors
 //! - GitHub releases
+//! - GitLab releases
+//! - Gitea / Forgejo releases
+//! - crates.io (and alternate registries sparse index)
+//! - npm registry
+//! - PyPI
+//! - Container registries (OCI distribution tags)
+//! - Static manifest file (JSON / TOML / YAML)
+//! - Atom / RSS feeds
+//! - Git tags (any git HTTP server)
+//! - Sparkle appcast
+//! - Aggregate of several vendors (highest version)
 //! - Custom rest api
 //!
 //! ## GitHub Example:
@@ -26,8 +37,10 @@
 //!     // run command execute upversion check in the background and finish immediately.
 //!     version_context.run("0.0.1")?;
 //!
-//!     // sleep here simulator your program
-//!     std::thread::sleep(std::time::Duration::from_secs(3));
+//!     // your program logic here
+//!
+//!     // wait until the check is done, up to the given timeout
+//!     version_context.wait(std::time::Duration::from_secs(3));
 //!
 //!     // at the end of your program, you can call printstd to print to the STDOUT a alert information for a new version which released
 //!     version_context.printstd();
@@ -44,7 +57,11 @@
 //!
 mod context;
 mod data;
+mod policy;
+mod state;
 mod template;
 pub mod vendors;
 
 pub use self::context::CheckVersion;
+pub use self::data::{CheckStatus, NewerReleaseVersion, RateLimited};
+pub use self::policy::SkipReason;
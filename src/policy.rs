@@ -0,0 +1,120 @@
+use std::env;
+use std::fmt;
+
+/// Environment variables that disable the check for any application
+const OPT_OUT_ENV_VARS: &[&str] = &["NO_UPDATE_NOTIFIER", "DO_NOT_TRACK"];
+
+/// Environment variables that set by common CI providers
+const CI_ENV_VARS: &[&str] = &[
+    "CI",
+    "CONTINUOUS_INTEGRATION",
+    "GITHUB_ACTIONS",
+    "GITLAB_CI",
+    "BUILDKITE",
+    "CIRCLECI",
+    "TRAVIS",
+    "JENKINS_URL",
+    "TEAMCITY_VERSION",
+    "TF_BUILD",
+    "CODEBUILD_BUILD_ID",
+    "BITBUCKET_BUILD_NUMBER",
+    "APPVEYOR",
+    "DRONE",
+];
+
+/// The reason the version check was skipped
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SkipReason {
+    /// The user opted out with the given environment variable
+    OptOut(String),
+    /// Running in CI, detected by the given environment variable
+    Ci(String),
+}
+
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OptOut(var) => write!(f, "opted out with {} environment variable", var),
+            Self::Ci(var) => write!(f, "running in CI ({} environment variable is set)", var),
+        }
+    }
+}
+
+/// App specific opt out environment variable: `<APP>_NO_UPDATE_CHECK`
+fn app_env_var(app_name: &str) -> String {
+    let app = app_name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+    format!("{}_NO_UPDATE_CHECK", app)
+}
+
+/// Variable is enabled when it set with any value except empty, `0` or `false`
+fn is_enabled(value: Option<String>) -> bool {
+    match value {
+        Some(value) => {
+            let value = value.trim().to_lowercase();
+            !value.is_empty() && value != "0" && value != "false"
+        }
+        None => false,
+    }
+}
+
+/// Return the reason the check should be skipped, by the given environment lookup
+fn skip_reason_with<F>(app_name: &str, lookup: F) -> Option<SkipReason>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let app_var = app_env_var(app_name);
+    let opt_out = OPT_OUT_ENV_VARS
+        .iter()
+        .map(|var| var.to_string())
+        .chain(std::iter::once(app_var))
+        .find(|var| is_enabled(lookup(var)));
+    if let Some(var) = opt_out {
+        return Some(SkipReason::OptOut(var));
+    }
+
+    CI_ENV_VARS
+        .iter()
+        .find(|var| is_enabled(lookup(var)))
+        .map(|var| SkipReason::Ci(var.to_string()))
+}
+
+/// Return the reason the check should be skipped, by the process environment variables
+pub fn skip_reason(app_name: &str) -> Option<SkipReason> {
+    skip_reason_with(app_name, |var| env::var(var).ok())
+}
+
+#[cfg(test)]
+mod test_policy {
+    use super::*;
+    use insta::assert_debug_snapshot;
+    use std::collections::HashMap;
+
+    fn skip_reason_of(vars: &[(&str, &str)]) -> Option<SkipReason> {
+        let vars = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect::<HashMap<_, _>>();
+        skip_reason_with("my-app", |var| vars.get(var).cloned())
+    }
+
+    #[test]
+    fn can_get_skip_reason() {
+        assert_debug_snapshot!(vec![
+            skip_reason_of(&[]),
+            skip_reason_of(&[("NO_UPDATE_NOTIFIER", "1")]),
+            skip_reason_of(&[("MY_APP_NO_UPDATE_CHECK", "true")]),
+            skip_reason_of(&[("DO_NOT_TRACK", "0"), ("CI", "false")]),
+            skip_reason_of(&[("GITHUB_ACTIONS", "true"), ("DO_NOT_TRACK", "1")]),
+            skip_reason_of(&[("BUILDKITE", "true")]),
+        ]);
+    }
+}
@@ -0,0 +1,166 @@
+use crate::data::{CachedRelease, Release};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Current time as seconds since the unix epoch
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Check state that persist between runs of the application
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct State {
+    /// Vendors (by vendor id) that are rate limited, and the reset time as seconds since the unix epoch
+    #[serde(default)]
+    pub rate_limited_until: HashMap<String, u64>,
+    /// Last release of vendors (by vendor id) that support conditional requests
+    #[serde(default)]
+    pub releases: HashMap<String, CachedRelease>,
+    /// Last successful check time as seconds since the unix epoch
+    #[serde(default)]
+    pub last_check: Option<u64>,
+    /// Release of the last successful check
+    #[serde(default)]
+    pub last_release: Option<Release>,
+}
+
+impl State {
+    /// Default state file location: `<platform cache dir>/upversion/<app_name>.json`.
+    /// Characters other than ASCII letters, digits, `-` and `_` are replaced with `_`, so the app name can't
+    /// point outside the state directory (`a/b`, `../x`).
+    pub fn default_path(app_name: &str) -> Option<PathBuf> {
+        let file_name = app_name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect::<String>();
+        dirs::cache_dir().map(|dir| dir.join("upversion").join(format!("{}.json", file_name)))
+    }
+
+    /// Load the state from the given file. A missing or invalid file returns an empty state
+    pub fn load(path: &Path) -> Self {
+        let content = match fs::read(path) {
+            Ok(c) => c,
+            Err(e) => {
+                log::debug!("could not read state file {:?}. err: {:?}", path, e);
+                return Self::default();
+            }
+        };
+
+        match serde_json::from_slice(&content) {
+            Ok(s) => s,
+            Err(e) => {
+                log::debug!("invalid state file {:?}. err: {:?}", path, e);
+                Self::default()
+            }
+        }
+    }
+
+    /// Write the state to the given file, the parent directories are created when missing
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_vec(self)?)?;
+        Ok(())
+    }
+
+    /// Return the last release when the last successful check is in the interval
+    pub fn recent_release(&self, interval: Duration, now: u64) -> Option<Release> {
+        let last_check = self.last_check?;
+        if last_check > now || now - last_check >= interval.as_secs() {
+            return None;
+        }
+        self.last_release.clone()
+    }
+
+    /// Return the rate limit reset time when the vendor should not be queried at `now`
+    pub fn rate_limited(&self, id: &str, now: u64) -> Option<u64> {
+        self.rate_limited_until
+            .get(id)
+            .filter(|until| **until > now)
+            .copied()
+    }
+}
+
+#[cfg(test)]
+mod test_state {
+    use super::*;
+
+    #[test]
+    fn can_save_and_load_state() {
+        let path = std::env::temp_dir()
+            .join(format!("upversion-test-{}", std::process::id()))
+            .join("state.json");
+
+        let mut state = State::default();
+        state
+            .rate_limited_until
+            .insert("https://api.github.com/repos/owner/repo".to_string(), 100);
+        state.save(&path).unwrap();
+
+        let state = State::load(&path);
+        assert_eq!(state.rate_limited("http://127.0.0.1:3000", 50), None);
+        assert_eq!(
+            state.rate_limited("https://api.github.com/repos/owner/repo", 50),
+            Some(100)
+        );
+        assert_eq!(
+            state.rate_limited("https://api.github.com/repos/owner/repo", 100),
+            None
+        );
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn can_sanitize_default_path() {
+        let path = State::default_path("a/b").unwrap();
+        assert_eq!(path.file_name().unwrap(), "a_b.json");
+        assert_eq!(path.parent().unwrap().file_name().unwrap(), "upversion");
+
+        let path = State::default_path("../x").unwrap();
+        assert_eq!(path.file_name().unwrap(), "___x.json");
+        assert_eq!(path.parent().unwrap().file_name().unwrap(), "upversion");
+    }
+
+    #[test]
+    fn can_get_recent_release() {
+        let state = State {
+            last_check: Some(1000),
+            last_release: Some(Release {
+                version: "1.0.0".to_string(),
+                downloads_releases: vec![],
+                download_link: None,
+                install_command: None,
+                source: None,
+            }),
+            ..State::default()
+        };
+
+        let interval = Duration::from_secs(100);
+        assert!(state.recent_release(interval, 1050).is_some());
+        assert!(state.recent_release(interval, 1100).is_none());
+        // clock changed backward
+        assert!(state.recent_release(interval, 900).is_none());
+        assert!(State::default().recent_release(interval, 1050).is_none());
+    }
+
+    #[test]
+    fn can_load_missing_state() {
+        let state = State::load(Path::new("/not-exists/upversion/state.json"));
+        assert!(state.rate_limited_until.is_empty());
+    }
+}
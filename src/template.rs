@@ -7,6 +7,8 @@ pub fn new_version_available(
     new_version: &semver::Version,
     current_version: &semver::Version,
     download_link: Option<String>,
+    install_command: Option<String>,
+    source: Option<String>,
 ) -> Result<String> {
     let mut tera = Tera::default();
     let mut ctx = Context::new();
@@ -16,6 +18,12 @@ pub fn new_version_available(
     if let Some(download_link) = download_link {
         ctx.insert("download_link", &download_link);
     }
+    if let Some(install_command) = install_command {
+        ctx.insert("install_command", &install_command);
+    }
+    if let Some(source) = source {
+        ctx.insert("source", &source);
+    }
 
     Ok(tera.render_str(templete, &ctx)?)
 }
@@ -33,7 +41,9 @@ mod test_template {
             "app-name-template",
             &semver::Version::parse("1.0.0").unwrap(),
             &semver::Version::parse("0.1.1").unwrap(),
-            Some("https://foo.bar".to_string())
+            Some("https://foo.bar".to_string()),
+            None,
+            None
         ));
     }
 
@@ -45,6 +55,22 @@ mod test_template {
             "app-name-template",
             &semver::Version::parse("1.0.0").unwrap(),
             &semver::Version::parse("0.1.1").unwrap(),
+            None,
+            None,
+            None
+        ));
+    }
+
+    #[test]
+    fn can_render_install_command() {
+        let template  = "app_name:: {{ app_name }} version: {{ new_version }} {% if download_link %}| Link: {{ download_link }} {% elif install_command %}| Install: {{ install_command }} {% endif %}";
+        assert_debug_snapshot!(new_version_available(
+            template,
+            "app-name-template",
+            &semver::Version::parse("1.0.0").unwrap(),
+            &semver::Version::parse("0.1.1").unwrap(),
+            None,
+            Some("cargo install app-name-template".to_string()),
             None
         ));
     }
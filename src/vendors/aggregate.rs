@@ -0,0 +1,314 @@
+use crate::data;
+use anyhow::anyhow;
+use anyhow::Result;
+use curl::easy::Easy;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration;
+
+type SourceVendor = Arc<Mutex<Box<dyn data::Vendor + Send>>>;
+
+/// Aggregate vendor
+///
+/// Query several vendors concurrently and return the release with the highest version, so the notification
+/// reflect the freshest channel. The name of the source is set to the release, and available in the template
+/// as `{{ source }}`.
+///
+/// Each source is queried with its own HTTP client, limited by the given timeout. The user agent of these clients
+/// can be set with [`AggregateVendor::with_user_agent`].
+///
+/// ## Usage Example
+/// ```
+/// use anyhow::Result;
+/// use upversion::vendors::{AggregateVendor, CratesIoVendor, GitHubVendor};
+/// use upversion::CheckVersion;
+///
+/// fn main() -> Result<()> {
+///     let timeout = 2; // in seconds
+///     let aggregate = Box::new(AggregateVendor::new(
+///         vec![
+///             ("github", Box::new(GitHubVendor::new("owner", "repo"))),
+///             ("crates.io", Box::new(CratesIoVendor::new("crate-name"))),
+///         ],
+///         timeout,
+///     ).with_user_agent("upversion-app-name"));
+///     let version_context = CheckVersion::new("app-name", aggregate, timeout)?;
+///
+///     // run command execute upversion check in the background and finish immediately.
+///     version_context.run("0.0.1")?;
+///
+///     // your program logic here
+///
+///     // wait until the check is done, up to the given timeout
+///     version_context.wait(std::time::Duration::from_secs(3));
+///
+///     // at the end of your program, you can call printstd_with_template to print to the STDOUT a alert information for a new version which released
+///     version_context.printstd_with_template("Newer version available: {{ new_version }} (from {{ source }})");
+///     Ok(())
+/// }
+/// ```
+pub struct AggregateVendor {
+    sources: Vec<(String, SourceVendor)>,
+    timeout: u64,
+    min_sources: usize,
+    user_agent: String,
+}
+
+impl AggregateVendor {
+    /// create aggregate instance that return the highest version of all the sources
+    ///
+    /// # Arguments
+    ///
+    /// * `sources` - list of source name and vendor
+    /// * `timeout` - request timeout of each source (in seconds)
+    pub fn new(sources: Vec<(&str, Box<dyn data::Vendor + Send>)>, timeout: u64) -> Self {
+        Self::custom(sources, timeout, None)
+    }
+
+    /// create aggregate instance
+    ///
+    /// # Arguments
+    ///
+    /// * `sources` - list of source name and vendor
+    /// * `timeout` - request timeout of each source (in seconds)
+    /// * `min_sources` - consider only versions that returned by at least N sources
+    ///
+    pub fn custom(
+        sources: Vec<(&str, Box<dyn data::Vendor + Send>)>,
+        timeout: u64,
+        min_sources: Option<usize>,
+    ) -> Self {
+        Self {
+            sources: sources
+                .into_iter()
+                .map(|(name, vendor)| (name.to_string(), Arc::new(Mutex::new(vendor))))
+                .collect(),
+            timeout,
+            min_sources: min_sources.unwrap_or(1),
+            user_agent: "upversion".to_string(),
+        }
+    }
+
+    /// set the user agent of the sources requests, by default `upversion`.
+    /// Use the same value as the application client (`upversion-<app-name>`) to keep the requests consistent.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_agent` - `User-Agent` header value
+    pub fn with_user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = user_agent.to_string();
+        self
+    }
+
+    fn client(timeout: u64, user_agent: &str) -> Result<Easy> {
+        let mut easy = Easy::new();
+        easy.timeout(Duration::from_secs(timeout))?;
+        easy.useragent(user_agent)?;
+        Ok(easy)
+    }
+
+    /// Query all the sources concurrently, the failed sources are ignored
+    fn releases(&self) -> Vec<(String, data::Release)> {
+        let handles = self
+            .sources
+            .iter()
+            .map(|(name, vendor)| {
+                let name = name.clone();
+                let vendor = vendor.clone();
+                let timeout = self.timeout;
+                let user_agent = self.user_agent.clone();
+                thread::spawn(move || -> Result<(String, data::Release)> {
+                    let client = Mutex::new(Self::client(timeout, &user_agent)?);
+                    let vendor = match vendor.lock() {
+                        Ok(v) => v,
+                        Err(e) => return Err(anyhow!("could not lock vendor. err: {:?}", e)),
+                    };
+                    let client = match client.lock() {
+                        Ok(c) => c,
+                        Err(e) => return Err(anyhow!("could not lock client. err: {:?}", e)),
+                    };
+                    let release = vendor.get(client)?;
+                    Ok((name, release))
+                })
+            })
+            .collect::<Vec<_>>();
+
+        handles
+            .into_iter()
+            .zip(self.sources.iter())
+            .filter_map(|(handle, (name, _))| match handle.join() {
+                Ok(Ok(release)) => Some(release),
+                Ok(Err(e)) => {
+                    log::debug!("source {} failed. err: {:?}", name, e);
+                    None
+                }
+                Err(e) => {
+                    log::debug!("source {} panicked. err: {:?}", name, e);
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Select the highest version that returned by at least `min_sources` sources.
+    /// When several sources returned the selected version, the first source (by order) is used.
+    fn select(&self, releases: Vec<(String, data::Release)>) -> Result<(String, data::Release)> {
+        let releases = releases
+            .into_iter()
+            .filter_map(|(name, release)| {
+                match semver::Version::parse(release.version.trim_start_matches('v')) {
+                    Ok(version) => Some((version, name, release)),
+                    Err(e) => {
+                        log::debug!("source {} invalid version. err: {:?}", name, e);
+                        None
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let selected = releases
+            .iter()
+            .filter(|(version, _, _)| {
+                releases.iter().filter(|(v, _, _)| v == version).count() >= self.min_sources
+            })
+            .max_by(|(a, _, _), (b, _, _)| a.cmp(b))
+            .map(|(version, _, _)| version.clone());
+
+        let selected = match selected {
+            Some(v) => v,
+            None => {
+                return Err(anyhow!(
+                    "release not found in at least {} sources",
+                    self.min_sources
+                ))
+            }
+        };
+
+        match releases
+            .into_iter()
+            .find(|(version, _, _)| *version == selected)
+        {
+            Some((_, name, release)) => Ok((name, release)),
+            None => Err(anyhow!("release not found")),
+        }
+    }
+}
+
+impl data::Vendor for AggregateVendor {
+    fn id(&self) -> String {
+        let sources = self
+            .sources
+            .iter()
+            .map(|(name, vendor)| match vendor.lock() {
+                Ok(v) => format!("{}={}", name, v.id()),
+                Err(_) => name.clone(),
+            })
+            .collect::<Vec<_>>();
+        format!("aggregate:{}", sources.join(","))
+    }
+
+    /// Get the highest version from all the sources, the given client is not used since the sources are
+    /// queried concurrently
+    fn get(&self, _client: MutexGuard<Easy>) -> Result<data::Release> {
+        let (name, release) = self.select(self.releases())?;
+        Ok(data::Release {
+            source: Some(name),
+            ..release
+        })
+    }
+}
+
+#[cfg(test)]
+mod vendor_aggregate_test {
+    use crate::data::Vendor;
+
+    use super::{AggregateVendor, Easy};
+    use crate::vendors::{Api, GitHubVendor};
+    use insta::assert_debug_snapshot;
+    use std::sync::{Arc, Mutex};
+
+    fn aggregate(min_sources: Option<usize>) -> AggregateVendor {
+        let url = &mockito::server_url();
+        AggregateVendor::custom(
+            vec![
+                (
+                    "github",
+                    Box::new(GitHubVendor::custom("owner", "repo", Some(url.to_string()))),
+                ),
+                (
+                    "mirror",
+                    Box::new(Api::new(format!("{}/mirror", url).as_str())),
+                ),
+                ("brew", Box::new(Api::new(format!("{}/brew", url).as_str()))),
+                ("down", Box::new(Api::new(format!("{}/down", url).as_str()))),
+            ],
+            2,
+            min_sources,
+        )
+    }
+
+    fn mock_sources() -> Vec<mockito::Mock> {
+        vec![
+            mockito::mock("GET", "/repos/owner/repo/releases?per_page=10&page=1")
+                .with_body(r#"[{"tag_name": "v1.1.0", "assets": [{"browser_download_url": "https://github.com/foo"}]}]"#)
+                .with_status(200)
+                .create(),
+            mockito::mock("GET", "/mirror")
+                .with_body(r#"{"version": "1.1.0", "release_downloads": []}"#)
+                .with_status(200)
+                .create(),
+            mockito::mock("GET", "/brew")
+                .with_body(r#"{"version": "1.2.0", "release_downloads": []}"#)
+                .with_status(200)
+                .create(),
+            mockito::mock("GET", "/down").with_status(500).create(),
+        ]
+    }
+
+    #[test]
+    fn can_get_highest_release() {
+        let _m = mock_sources();
+
+        let easy = Easy::new();
+        assert_debug_snapshot!(aggregate(None).get(Arc::new(Mutex::new(easy)).lock().unwrap()));
+    }
+
+    #[test]
+    fn can_get_release_with_min_sources() {
+        let _m = mock_sources();
+
+        let easy = Easy::new();
+        assert_debug_snapshot!(aggregate(Some(2)).get(Arc::new(Mutex::new(easy)).lock().unwrap()));
+    }
+
+    #[test]
+    fn can_get_release_with_user_agent() {
+        let url = &mockito::server_url();
+
+        let aggregate = AggregateVendor::new(
+            vec![(
+                "agent",
+                Box::new(Api::new(format!("{}/agent", url).as_str())),
+            )],
+            2,
+        )
+        .with_user_agent("upversion-app");
+
+        let _m = mockito::mock("GET", "/agent")
+            .match_header("user-agent", "upversion-app")
+            .with_body(r#"{"version": "1.1.0", "release_downloads": []}"#)
+            .with_status(200)
+            .create();
+
+        let easy = Easy::new();
+        assert_debug_snapshot!(aggregate.get(Arc::new(Mutex::new(easy)).lock().unwrap()));
+    }
+
+    #[test]
+    fn can_get_release_without_enough_sources() {
+        let _m = mock_sources();
+
+        let easy = Easy::new();
+        assert_debug_snapshot!(aggregate(Some(3)).get(Arc::new(Mutex::new(easy)).lock().unwrap()));
+    }
+}
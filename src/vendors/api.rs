@@ -1,7 +1,8 @@
 use crate::data;
+use crate::vendors::http;
 use anyhow::anyhow;
 use anyhow::Result;
-use curl::easy::Easy;
+use curl::easy::{Easy, List};
 use serde_json::Value;
 use std::sync::MutexGuard;
 
@@ -28,8 +29,10 @@ const DESERIALIZE_DOWNLOAD_URL_KEY: &str = "release_downloads";
 ///     // run command execute upversion check in the background and finish immediately.
 ///     version_context.run("0.0.1")?;
 ///
-///     // sleep here simulator your program
-///     std::thread::sleep(std::time::Duration::from_secs(3));
+///     // your program logic here
+///
+///     // wait until the check is done, up to the given timeout
+///     version_context.wait(std::time::Duration::from_secs(3));
 ///
 ///     // at the end of your program, you can call printstd to print to the STDOUT a alert information for a new version which released
 ///     version_context.printstd();
@@ -115,8 +118,10 @@ impl Api {
     ///     // run command execute upversion check in the background and finish immediately.
     ///     version_context.run("0.0.1")?;
     ///
-    ///     // sleep here simulator your program
-    ///     std::thread::sleep(std::time::Duration::from_secs(3));
+    ///     // your program logic here
+    ///
+    ///     // wait until the check is done, up to the given timeout
+    ///     version_context.wait(std::time::Duration::from_secs(3));
     ///
     ///     // at the end of your program, you can call printstd to print to the STDOUT a alert information for a new version which released
     ///     version_context.printstd();
@@ -133,39 +138,57 @@ impl Api {
     fn get_value_with_error(&self, v: &Value, key: &str) -> Result<Value> {
         match v.get(key) {
             Some(value) => Ok(value.clone()),
-            _ => return Err(anyhow!("key: {} not found", key)),
+            _ => Err(anyhow!("key: {} not found", key)),
         }
     }
 }
 impl data::Vendor for Api {
+    fn id(&self) -> String {
+        format!(
+            "{}#{},{}",
+            self.url, self.deserialize_response.version, self.deserialize_response.download_url
+        )
+    }
+
     fn get(&self, client: MutexGuard<Easy>) -> Result<data::Release> {
-        let mut json = Vec::new();
+        Ok(self.get_conditional(client, None)?.release)
+    }
+
+    /// Get the release, the cached release is returned when the endpoint responds with `304 Not Modified`
+    fn get_conditional(
+        &self,
+        client: MutexGuard<Easy>,
+        cached: Option<&data::CachedRelease>,
+    ) -> Result<data::CachedRelease> {
         let mut client = client;
-        client.url(&self.url)?;
 
-        {
-            let mut transfer = client.transfer();
-            transfer
-                .write_function(|data| {
-                    json.extend_from_slice(data);
-                    Ok(data.len())
-                })
-                .unwrap();
-            transfer.perform()?;
-        }
+        let mut headers = List::new();
+        http::append_conditional_headers(&mut headers, cached)?;
 
-        let response: Value = serde_json::from_slice(&json)?;
+        let response = http::get(&mut client, &self.url, headers)?;
+        if let (true, Some(cached)) = (response.is_not_modified(), cached) {
+            return Ok(cached.clone());
+        }
+        let validators = response.validators();
+        let response: Value = serde_json::from_slice(&response.body)?;
         let download_releases: Vec<String> = serde_json::from_value(
             self.get_value_with_error(&response, &self.deserialize_response.download_url)?,
         )?;
 
-        Ok(data::Release {
+        let release = data::Release {
             version: self
                 .get_value_with_error(&response, &self.deserialize_response.version)?
                 .as_str()
                 .unwrap()
                 .to_string(),
             downloads_releases: download_releases,
+            download_link: None,
+            install_command: None,
+            source: None,
+        };
+        Ok(data::CachedRelease {
+            release,
+            validators,
         })
     }
 }
@@ -244,4 +267,34 @@ mod vendor_api_test {
         let easy = Easy::new();
         assert_debug_snapshot!(api.get(Arc::new(Mutex::new(easy)).lock().unwrap()));
     }
+
+    #[test]
+    fn can_get_cached_release_when_not_modified() {
+        let url = &mockito::server_url();
+
+        let api = Api::new(format!("{}/conditional", url).as_str());
+
+        let m = mockito::mock("GET", "/conditional")
+            .with_header("etag", "\"abc\"")
+            .with_body(r#"{"version": "1.0.0", "release_downloads": []}"#)
+            .with_status(200)
+            .create();
+
+        let easy = Easy::new();
+        let cached = api
+            .get_conditional(Arc::new(Mutex::new(easy)).lock().unwrap(), None)
+            .unwrap();
+        assert_debug_snapshot!(cached);
+        drop(m);
+
+        let _m = mockito::mock("GET", "/conditional")
+            .match_header("if-none-match", "\"abc\"")
+            .with_status(304)
+            .create();
+
+        let easy = Easy::new();
+        assert_debug_snapshot!(
+            api.get_conditional(Arc::new(Mutex::new(easy)).lock().unwrap(), Some(&cached))
+        );
+    }
 }
@@ -0,0 +1,300 @@
+use crate::data;
+use crate::vendors::http;
+use anyhow::anyhow;
+use anyhow::Result;
+use curl::easy::{Easy, List};
+use roxmltree::Node;
+use std::env;
+use std::sync::MutexGuard;
+
+/// Sparkle XML namespace
+const SPARKLE_NS: &str = "http://www.andymatuschak.org/xml-namespaces/sparkle";
+
+/// Appcast item details
+#[derive(Debug)]
+struct AppcastItem {
+    version: semver::Version,
+    url: Option<String>,
+    os: Option<String>,
+    minimum_system_version: Option<semver::Version>,
+}
+
+/// Sparkle appcast vendor
+///
+/// Check if there is a new version from Sparkle appcast feed.
+/// The version is taken from `sparkle:shortVersionString` (or `sparkle:version` when not exists), and the item
+/// enclosure URL is used as the download link. Items with `sparkle:os` of other platform are ignored,
+/// items without `sparkle:os` match any platform. The architecture part of `sparkle:os` (`windows-x64`,
+/// `windows-arm64`) is matched when it exists.
+///
+/// ## Usage Example
+/// ```
+/// use anyhow::Result;
+/// use upversion::vendors::AppcastVendor;
+/// use upversion::CheckVersion;
+///
+/// fn main() -> Result<()> {
+///     let appcast = Box::new(AppcastVendor::new("https://example.com/appcast.xml"));
+///     let timeout = 2; // in seconds
+///     let version_context = CheckVersion::new("app-name", appcast, timeout)?;
+///
+///     // run command execute upversion check in the background and finish immediately.
+///     version_context.run("0.0.1")?;
+///
+///     // your program logic here
+///
+///     // wait until the check is done, up to the given timeout
+///     version_context.wait(std::time::Duration::from_secs(3));
+///
+///     // at the end of your program, you can call printstd to print to the STDOUT a alert information for a new version which released
+///     version_context.printstd();
+///     Ok(())
+/// }
+/// ```
+pub struct AppcastVendor {
+    url: String,
+    system_version: Option<semver::Version>,
+}
+
+impl AppcastVendor {
+    /// create appcast instance
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - appcast URL
+    pub fn new(url: &str) -> Self {
+        Self::custom(url, None)
+    }
+
+    /// create appcast instance
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - appcast URL
+    /// * `system_version` - running OS version, items with higher `sparkle:minimumSystemVersion` are ignored
+    ///
+    pub fn custom(url: &str, system_version: Option<&str>) -> Self {
+        Self {
+            url: url.to_string(),
+            system_version: system_version.and_then(Self::parse_version),
+        }
+    }
+
+    /// Parse appcast version, missing segments are filled with zeros (`10.13` -> `10.13.0`)
+    fn parse_version(version: &str) -> Option<semver::Version> {
+        let version = version.trim().trim_start_matches('v');
+        if let Ok(version) = semver::Version::parse(version) {
+            return Some(version);
+        }
+
+        let mut segments = version
+            .split('.')
+            .map(|segment| segment.parse::<u64>().ok())
+            .collect::<Option<Vec<_>>>()?;
+        if segments.len() > 3 {
+            return None;
+        }
+        segments.resize(3, 0);
+        Some(semver::Version::new(segments[0], segments[1], segments[2]))
+    }
+
+    /// Get sparkle value from the item element or from the enclosure attribute
+    fn sparkle_value<'a>(
+        item: &Node<'a, 'a>,
+        enclosure: Option<&Node<'a, 'a>>,
+        name: &str,
+    ) -> Option<&'a str> {
+        item.children()
+            .find(|c| c.tag_name().namespace() == Some(SPARKLE_NS) && c.tag_name().name() == name)
+            .and_then(|c| c.text())
+            .or_else(|| enclosure.and_then(|e| e.attribute((SPARKLE_NS, name))))
+    }
+
+    fn parse_items(content: &str) -> Result<Vec<AppcastItem>> {
+        let doc = roxmltree::Document::parse(content)?;
+
+        let items = doc
+            .descendants()
+            .filter(|node| node.tag_name().name() == "item")
+            .filter_map(|item| {
+                let enclosure = item.children().find(|c| c.tag_name().name() == "enclosure");
+
+                let version = Self::sparkle_value(&item, enclosure.as_ref(), "shortVersionString")
+                    .or_else(|| Self::sparkle_value(&item, enclosure.as_ref(), "version"))
+                    .and_then(Self::parse_version)?;
+
+                Some(AppcastItem {
+                    version,
+                    url: enclosure
+                        .and_then(|e| e.attribute("url"))
+                        .map(|url| url.to_string()),
+                    os: Self::sparkle_value(&item, enclosure.as_ref(), "os")
+                        .map(|os| os.to_lowercase()),
+                    minimum_system_version: Self::sparkle_value(
+                        &item,
+                        enclosure.as_ref(),
+                        "minimumSystemVersion",
+                    )
+                    .and_then(Self::parse_version),
+                })
+            })
+            .collect();
+        Ok(items)
+    }
+
+    /// Map `sparkle:os` architecture to Rust architecture name
+    fn arch(arch: &str) -> &str {
+        match arch {
+            "x64" | "amd64" => "x86_64",
+            "arm64" => "aarch64",
+            arch => arch,
+        }
+    }
+
+    fn is_supported(&self, item: &AppcastItem, os: &str, arch: &str) -> bool {
+        if let Some(item_os) = &item.os {
+            let (item_os, item_arch) = match item_os.split_once('-') {
+                Some((item_os, item_arch)) => (item_os, Some(item_arch)),
+                None => (item_os.as_str(), None),
+            };
+            let item_os = if item_os == "osx" { "macos" } else { item_os };
+            if item_os != os {
+                return false;
+            }
+            if let Some(item_arch) = item_arch {
+                if Self::arch(item_arch) != arch {
+                    return false;
+                }
+            }
+        }
+
+        match (&self.system_version, &item.minimum_system_version) {
+            (Some(system_version), Some(minimum)) => system_version >= minimum,
+            _ => true,
+        }
+    }
+
+    fn release(&self, items: Vec<AppcastItem>, os: &str, arch: &str) -> Result<data::Release> {
+        let item = items
+            .into_iter()
+            .filter(|item| item.version.pre.is_empty())
+            .filter(|item| self.is_supported(item, os, arch))
+            .max_by(|a, b| a.version.cmp(&b.version));
+
+        match item {
+            Some(item) => Ok(data::Release {
+                version: item.version.to_string(),
+                downloads_releases: vec![],
+                download_link: item.url,
+                install_command: None,
+                source: None,
+            }),
+            None => Err(anyhow!("appcast items not found")),
+        }
+    }
+}
+
+impl data::Vendor for AppcastVendor {
+    fn id(&self) -> String {
+        self.url.clone()
+    }
+
+    /// Get the highest version from the appcast items
+    fn get(&self, client: MutexGuard<Easy>) -> Result<data::Release> {
+        let mut client = client;
+
+        let response = http::get(&mut client, &self.url, List::new())?.error_for_status()?;
+        let items = Self::parse_items(&String::from_utf8_lossy(&response.body))?;
+
+        self.release(items, env::consts::OS, env::consts::ARCH)
+    }
+}
+
+#[cfg(test)]
+mod vendor_appcast_test {
+    use crate::data::Vendor;
+
+    use super::{AppcastVendor, Easy};
+    use insta::assert_debug_snapshot;
+    use std::sync::{Arc, Mutex};
+
+    const APPCAST: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<rss version="2.0" xmlns:sparkle="http://www.andymatuschak.org/xml-namespaces/sparkle">
+  <channel>
+    <title>app</title>
+    <item>
+      <title>Version 1.1</title>
+      <sparkle:version>110</sparkle:version>
+      <sparkle:shortVersionString>1.1</sparkle:shortVersionString>
+      <enclosure url="https://foo.test/app-1.1-macos.zip" sparkle:os="macos" length="100" type="application/octet-stream"/>
+    </item>
+    <item>
+      <title>Version 1.1</title>
+      <enclosure url="https://foo.test/app-1.1-linux.tar.gz" sparkle:version="110" sparkle:shortVersionString="1.1" sparkle:os="linux" length="100" type="application/octet-stream"/>
+    </item>
+    <item>
+      <title>Version 2.0</title>
+      <sparkle:version>200</sparkle:version>
+      <sparkle:shortVersionString>2.0.0</sparkle:shortVersionString>
+      <sparkle:minimumSystemVersion>13.0</sparkle:minimumSystemVersion>
+      <enclosure url="https://foo.test/app-2.0.0-macos.zip" sparkle:os="macos" length="100" type="application/octet-stream"/>
+    </item>
+    <item>
+      <title>Version 1.5</title>
+      <enclosure url="https://foo.test/app-1.5-x64.msi" sparkle:version="150" sparkle:shortVersionString="1.5" sparkle:os="windows-x64" length="100" type="application/octet-stream"/>
+    </item>
+    <item>
+      <title>Version 1.6</title>
+      <enclosure url="https://foo.test/app-1.6-arm64.msi" sparkle:version="160" sparkle:shortVersionString="1.6" sparkle:os="windows-arm64" length="100" type="application/octet-stream"/>
+    </item>
+  </channel>
+</rss>
+"#;
+
+    #[test]
+    fn can_select_platform_item() {
+        let appcast = AppcastVendor::custom("https://foo.test/appcast.xml", Some("12.6"));
+        let items = AppcastVendor::parse_items(APPCAST).unwrap();
+        assert_debug_snapshot!(appcast.release(items, "macos", "x86_64"));
+
+        let appcast = AppcastVendor::custom("https://foo.test/appcast.xml", Some("13.1"));
+        let items = AppcastVendor::parse_items(APPCAST).unwrap();
+        assert_debug_snapshot!(appcast.release(items, "macos", "x86_64"));
+
+        let items = AppcastVendor::parse_items(APPCAST).unwrap();
+        assert_debug_snapshot!(appcast.release(items, "linux", "x86_64"));
+
+        let items = AppcastVendor::parse_items(APPCAST).unwrap();
+        assert_debug_snapshot!(appcast.release(items, "windows", "x86_64"));
+
+        let items = AppcastVendor::parse_items(APPCAST).unwrap();
+        assert_debug_snapshot!(appcast.release(items, "windows", "aarch64"));
+
+        let items = AppcastVendor::parse_items(APPCAST).unwrap();
+        assert_debug_snapshot!(appcast.release(items, "windows", "x86"));
+    }
+
+    #[test]
+    fn can_get_release_details() {
+        let url = &mockito::server_url();
+
+        let _m = mockito::mock("GET", "/appcast.xml")
+            .with_body(
+                r#"<rss version="2.0" xmlns:sparkle="http://www.andymatuschak.org/xml-namespaces/sparkle">
+  <channel>
+    <item>
+      <sparkle:version>3.1.0</sparkle:version>
+      <enclosure url="https://foo.test/app-3.1.0.zip"/>
+    </item>
+  </channel>
+</rss>"#,
+            )
+            .with_status(200)
+            .create();
+
+        let appcast = AppcastVendor::new(format!("{}/appcast.xml", url).as_str());
+
+        let easy = Easy::new();
+        assert_debug_snapshot!(appcast.get(Arc::new(Mutex::new(easy)).lock().unwrap()));
+    }
+}
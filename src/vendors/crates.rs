@@ -0,0 +1,289 @@
+use crate::data;
+use crate::vendors::http;
+use anyhow::anyhow;
+use anyhow::Result;
+use curl::easy::{Easy, List};
+use serde::{Deserialize, Serialize};
+use std::sync::MutexGuard;
+
+/// Default crates.io base URL
+const DEFAULT_CRATES_IO_URL: &str = "https://crates.io";
+
+#[derive(Deserialize, Serialize, Debug)]
+struct CrateResponse {
+    versions: Vec<CrateVersionResponse>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+struct CrateVersionResponse {
+    num: String,
+    yanked: bool,
+}
+
+/// Sparse index file entry (one JSON object per line)
+#[derive(Deserialize, Serialize, Debug)]
+struct IndexEntryResponse {
+    vers: String,
+    yanked: bool,
+}
+
+/// Where to read the crate versions from
+enum Registry {
+    /// crates.io compatible web API
+    Api(String),
+    /// sparse registry index
+    SparseIndex(String),
+}
+
+/// crates.io vendor
+///
+/// Check if there is a new version of a crate published to crates.io or to an alternate registry sparse index.
+/// Yanked versions and pre-releases are ignored, and the notification suggest to run `cargo install <crate>`.
+///
+/// ## Usage Example
+/// ```
+/// use anyhow::Result;
+/// use upversion::vendors::CratesIoVendor;
+/// use upversion::CheckVersion;
+///
+/// fn main() -> Result<()> {
+///     let crates_io = Box::new(CratesIoVendor::new("crate-name"));
+///     let timeout = 2; // in seconds
+///     let version_context = CheckVersion::new("app-name", crates_io, timeout)?;
+///
+///     // run command execute upversion check in the background and finish immediately.
+///     version_context.run("0.0.1")?;
+///
+///     // your program logic here
+///
+///     // wait until the check is done, up to the given timeout
+///     version_context.wait(std::time::Duration::from_secs(3));
+///
+///     // at the end of your program, you can call printstd to print to the STDOUT a alert information for a new version which released
+///     version_context.printstd();
+///     Ok(())
+/// }
+/// ```
+pub struct CratesIoVendor {
+    name: String,
+    registry: Registry,
+}
+
+impl CratesIoVendor {
+    /// create crates.io instance
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - crate name
+    pub fn new(name: &str) -> Self {
+        Self::custom(name, None)
+    }
+
+    /// create crates.io instance
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - crate name
+    /// * `base_url` - crates.io compatible API URL
+    ///
+    pub fn custom(name: &str, base_url: Option<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            registry: Registry::Api(
+                base_url
+                    .unwrap_or_else(|| DEFAULT_CRATES_IO_URL.to_string())
+                    .trim_end_matches('/')
+                    .to_string(),
+            ),
+        }
+    }
+
+    /// create crates.io instance that read the versions from a sparse registry index
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - crate name
+    /// * `index_url` - sparse index URL (for example: `sparse+https://index.crates.io/`)
+    ///
+    pub fn sparse_index(name: &str, index_url: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            registry: Registry::SparseIndex(
+                index_url
+                    .trim_start_matches("sparse+")
+                    .trim_end_matches('/')
+                    .to_string(),
+            ),
+        }
+    }
+
+    /// Path of the crate file in the index, see: <https://doc.rust-lang.org/cargo/reference/registry-index.html#index-files>
+    fn index_path(name: &str) -> String {
+        let name = name.to_lowercase();
+        match name.len() {
+            1 => format!("1/{}", name),
+            2 => format!("2/{}", name),
+            3 => format!("3/{}/{}", &name[..1], name),
+            _ => format!("{}/{}/{}", &name[..2], &name[2..4], name),
+        }
+    }
+
+    fn install_command(&self) -> String {
+        match &self.registry {
+            Registry::Api(_) => format!("cargo install {}", self.name),
+            Registry::SparseIndex(index_url) => {
+                format!("cargo install {} --index sparse+{}/", self.name, index_url)
+            }
+        }
+    }
+
+    /// Return all published versions as (version, yanked)
+    fn versions(&self, client: &mut Easy) -> Result<Vec<(String, bool)>> {
+        match &self.registry {
+            Registry::Api(base_url) => {
+                let url = format!("{}/api/v1/crates/{}", base_url, self.name);
+                let mut headers = List::new();
+                headers.append("accept: application/json")?;
+
+                let response = http::get(client, &url, headers)?.error_for_status()?;
+                let response: CrateResponse = serde_json::from_slice(&response.body)?;
+                Ok(response
+                    .versions
+                    .into_iter()
+                    .map(|v| (v.num, v.yanked))
+                    .collect())
+            }
+            Registry::SparseIndex(index_url) => {
+                let url = format!("{}/{}", index_url, Self::index_path(&self.name));
+
+                let response = http::get(client, &url, List::new())?.error_for_status()?;
+                String::from_utf8_lossy(&response.body)
+                    .lines()
+                    .filter(|line| !line.trim().is_empty())
+                    .map(|line| {
+                        let entry: IndexEntryResponse = serde_json::from_str(line)?;
+                        Ok((entry.vers, entry.yanked))
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+impl data::Vendor for CratesIoVendor {
+    fn id(&self) -> String {
+        match &self.registry {
+            Registry::Api(base_url) => format!("{}/api/v1/crates/{}", base_url, self.name),
+            Registry::SparseIndex(index_url) => format!("sparse+{}#{}", index_url, self.name),
+        }
+    }
+
+    /// Get newest non-yanked version
+    fn get(&self, client: MutexGuard<Easy>) -> Result<data::Release> {
+        let mut client = client;
+
+        let version = self
+            .versions(&mut client)?
+            .into_iter()
+            .filter(|(_, yanked)| !yanked)
+            .filter_map(|(version, _)| semver::Version::parse(&version).ok())
+            .filter(|version| version.pre.is_empty())
+            .max();
+
+        match version {
+            Some(version) => Ok(data::Release {
+                version: version.to_string(),
+                downloads_releases: vec![],
+                download_link: None,
+                install_command: Some(self.install_command()),
+                source: None,
+            }),
+            None => Err(anyhow!("crate versions not found")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod vendor_crates_test {
+    use crate::data::Vendor;
+
+    use super::{CratesIoVendor, Easy};
+    use insta::assert_debug_snapshot;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn can_get_index_path() {
+        assert_debug_snapshot!(vec![
+            CratesIoVendor::index_path("a"),
+            CratesIoVendor::index_path("ab"),
+            CratesIoVendor::index_path("abc"),
+            CratesIoVendor::index_path("Upversion"),
+        ]);
+    }
+
+    #[test]
+    fn can_get_release_details() {
+        let url = &mockito::server_url();
+
+        let crates_io = CratesIoVendor::custom("upversion", Some(url.to_string()));
+
+        let data = r#"
+        {
+            "crate": {
+                "name": "upversion",
+                "max_version": "0.3.0-beta.1"
+            },
+            "versions": [
+                { "num": "0.3.0-beta.1", "yanked": false },
+                { "num": "0.2.1", "yanked": true },
+                { "num": "0.2.0", "yanked": false },
+                { "num": "0.1.0", "yanked": false }
+            ]
+        }
+        "#;
+
+        let _m = mockito::mock("GET", "/api/v1/crates/upversion")
+            .with_body(data)
+            .with_status(200)
+            .create();
+
+        let easy = Easy::new();
+        assert_debug_snapshot!(crates_io.get(Arc::new(Mutex::new(easy)).lock().unwrap()));
+    }
+
+    #[test]
+    fn can_get_release_details_from_sparse_index() {
+        let url = &mockito::server_url();
+
+        let crates_io =
+            CratesIoVendor::sparse_index("upversion", format!("sparse+{}/", url).as_str());
+
+        let data = r#"{"name":"upversion","vers":"0.1.0","deps":[],"cksum":"","features":{},"yanked":false}
+{"name":"upversion","vers":"0.2.0","deps":[],"cksum":"","features":{},"yanked":false}
+{"name":"upversion","vers":"0.2.1","deps":[],"cksum":"","features":{},"yanked":true}
+"#;
+
+        let _m = mockito::mock("GET", "/up/ve/upversion")
+            .with_body(data)
+            .with_status(200)
+            .create();
+
+        let easy = Easy::new();
+        assert_debug_snapshot!(crates_io.get(Arc::new(Mutex::new(easy)).lock().unwrap()));
+    }
+
+    #[test]
+    fn can_get_release_details_without_versions() {
+        let url = &mockito::server_url();
+
+        let crates_io = CratesIoVendor::custom("upversion", Some(url.to_string()));
+
+        let _m = mockito::mock("GET", "/api/v1/crates/upversion")
+            .with_body(r#"{"versions": [{ "num": "0.1.0", "yanked": true }]}"#)
+            .with_status(200)
+            .create();
+
+        let easy = Easy::new();
+        assert_debug_snapshot!(crates_io.get(Arc::new(Mutex::new(easy)).lock().unwrap()));
+    }
+}
@@ -0,0 +1,258 @@
+use crate::data;
+use crate::vendors::http;
+use anyhow::anyhow;
+use anyhow::Result;
+use curl::easy::{Easy, List};
+use regex::Regex;
+use std::sync::MutexGuard;
+
+/// Default pattern for extracting the version from the entry title
+const DEFAULT_VERSION_PATTERN: &str = r"v?(\d+\.\d+\.\d+(?:-[0-9A-Za-z.-]+)?)";
+
+/// Feed entry details
+#[derive(Debug)]
+struct FeedEntry {
+    title: String,
+    link: Option<String>,
+}
+
+/// Atom/RSS feed vendor
+///
+/// Check if there is a new version from Atom or RSS feed entries (for example GitHub `releases.atom`).
+/// The version is extracted from the entry titles with a regex, and the highest stable version is used.
+/// When the regex has a capture group, the first group is used as the version.
+///
+/// ## Usage Example
+/// ```
+/// use anyhow::Result;
+/// use upversion::vendors::FeedVendor;
+/// use upversion::CheckVersion;
+///
+/// fn main() -> Result<()> {
+///     let feed = Box::new(FeedVendor::new("https://github.com/owner/repo/releases.atom"));
+///     let timeout = 2; // in seconds
+///     let version_context = CheckVersion::new("app-name", feed, timeout)?;
+///
+///     // run command execute upversion check in the background and finish immediately.
+///     version_context.run("0.0.1")?;
+///
+///     // your program logic here
+///
+///     // wait until the check is done, up to the given timeout
+///     version_context.wait(std::time::Duration::from_secs(3));
+///
+///     // at the end of your program, you can call printstd to print to the STDOUT a alert information for a new version which released
+///     version_context.printstd();
+///     Ok(())
+/// }
+/// ```
+pub struct FeedVendor {
+    url: String,
+    version_pattern: Regex,
+}
+
+impl FeedVendor {
+    /// create feed instance with the default version pattern
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - Atom/RSS feed URL
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            version_pattern: Regex::new(DEFAULT_VERSION_PATTERN).unwrap(),
+        }
+    }
+
+    /// create feed instance
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - Atom/RSS feed URL
+    /// * `version_pattern` - regex for extracting the version from the entry title
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the version pattern is not a valid regex
+    pub fn custom(url: &str, version_pattern: Option<&str>) -> Result<Self> {
+        Ok(Self {
+            url: url.to_string(),
+            version_pattern: Regex::new(version_pattern.unwrap_or(DEFAULT_VERSION_PATTERN))?,
+        })
+    }
+
+    /// Parse Atom `<entry>` or RSS `<item>` elements
+    fn parse_entries(content: &str) -> Result<Vec<FeedEntry>> {
+        let doc = roxmltree::Document::parse(content)?;
+
+        let entries = doc
+            .descendants()
+            .filter(|node| matches!(node.tag_name().name(), "entry" | "item"))
+            .map(|node| {
+                let child = |name: &str| node.children().find(|c| c.tag_name().name() == name);
+
+                let title = child("title")
+                    .and_then(|title| title.text())
+                    .unwrap_or_default()
+                    .trim()
+                    .to_string();
+
+                // atom link is in the `href` attribute, rss link is the element text
+                let link = child("link").and_then(|link| {
+                    link.attribute("href")
+                        .or_else(|| link.text())
+                        .map(|link| link.trim().to_string())
+                });
+
+                FeedEntry { title, link }
+            })
+            .collect();
+        Ok(entries)
+    }
+
+    fn extract_version(&self, title: &str) -> Option<semver::Version> {
+        let captures = self.version_pattern.captures(title)?;
+        let version = captures.get(1).or_else(|| captures.get(0))?.as_str();
+        semver::Version::parse(version.trim_start_matches('v')).ok()
+    }
+}
+
+impl data::Vendor for FeedVendor {
+    fn id(&self) -> String {
+        format!("{}#{}", self.url, self.version_pattern.as_str())
+    }
+
+    /// Get the highest version from the feed entries
+    fn get(&self, client: MutexGuard<Easy>) -> Result<data::Release> {
+        let mut client = client;
+
+        let mut headers = List::new();
+        headers.append("accept: application/atom+xml, application/rss+xml, application/xml")?;
+
+        let response = http::get(&mut client, &self.url, headers)?.error_for_status()?;
+        let entries = Self::parse_entries(&String::from_utf8_lossy(&response.body))?;
+
+        let entry = entries
+            .into_iter()
+            .filter_map(|entry| {
+                self.extract_version(&entry.title)
+                    .map(|version| (version, entry))
+            })
+            .filter(|(version, _)| version.pre.is_empty())
+            .max_by(|(a, _), (b, _)| a.cmp(b));
+
+        match entry {
+            Some((version, entry)) => Ok(data::Release {
+                version: version.to_string(),
+                downloads_releases: vec![],
+                download_link: entry.link,
+                install_command: None,
+                source: None,
+            }),
+            None => Err(anyhow!("feed entries with version not found")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod vendor_feed_test {
+    use crate::data::Vendor;
+
+    use super::{Easy, FeedVendor};
+    use insta::assert_debug_snapshot;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn can_get_release_details_from_atom() {
+        let url = &mockito::server_url();
+
+        let data = r#"<?xml version="1.0" encoding="UTF-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom" xml:lang="en-US">
+  <id>tag:github.com,2008:https://github.com/owner/repo/releases</id>
+  <title>Release notes from repo</title>
+  <entry>
+    <id>tag:github.com,2008:Repository/1/v1.3.0-rc.1</id>
+    <link rel="alternate" type="text/html" href="https://github.com/owner/repo/releases/tag/v1.3.0-rc.1"/>
+    <title>v1.3.0-rc.1</title>
+  </entry>
+  <entry>
+    <id>tag:github.com,2008:Repository/1/v1.2.0</id>
+    <link rel="alternate" type="text/html" href="https://github.com/owner/repo/releases/tag/v1.2.0"/>
+    <title>v1.2.0</title>
+  </entry>
+  <entry>
+    <id>tag:github.com,2008:Repository/1/v1.10.0</id>
+    <link rel="alternate" type="text/html" href="https://github.com/owner/repo/releases/tag/v1.10.0"/>
+    <title>Release v1.10.0</title>
+  </entry>
+</feed>
+"#;
+
+        let _m = mockito::mock("GET", "/releases.atom")
+            .with_body(data)
+            .with_status(200)
+            .create();
+
+        let feed = FeedVendor::new(format!("{}/releases.atom", url).as_str());
+
+        let easy = Easy::new();
+        assert_debug_snapshot!(feed.get(Arc::new(Mutex::new(easy)).lock().unwrap()));
+    }
+
+    #[test]
+    fn can_get_release_details_from_rss_with_custom_pattern() {
+        let url = &mockito::server_url();
+
+        let data = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+  <channel>
+    <title>app releases</title>
+    <item>
+      <title>app-cli 2.1.0 released</title>
+      <link>https://blog.test/app-cli-2.1.0</link>
+    </item>
+    <item>
+      <title>app-agent 3.0.0 released</title>
+      <link>https://blog.test/app-agent-3.0.0</link>
+    </item>
+  </channel>
+</rss>
+"#;
+
+        let _m = mockito::mock("GET", "/feed.xml")
+            .with_body(data)
+            .with_status(200)
+            .create();
+
+        let feed = FeedVendor::custom(
+            format!("{}/feed.xml", url).as_str(),
+            Some(r"app-cli (\d+\.\d+\.\d+)"),
+        )
+        .unwrap();
+
+        let easy = Easy::new();
+        assert_debug_snapshot!(feed.get(Arc::new(Mutex::new(easy)).lock().unwrap()));
+    }
+
+    #[test]
+    fn can_get_release_details_without_versions() {
+        let url = &mockito::server_url();
+
+        let _m = mockito::mock("GET", "/feed.xml")
+            .with_body(
+                r#"<rss version="2.0"><channel><item><title>hello</title></item></channel></rss>"#,
+            )
+            .with_status(200)
+            .create();
+
+        let feed = FeedVendor::new(format!("{}/feed.xml", url).as_str());
+
+        let easy = Easy::new();
+        assert_debug_snapshot!(feed.get(Arc::new(Mutex::new(easy)).lock().unwrap()));
+    }
+
+    #[test]
+    fn can_not_create_with_invalid_pattern() {
+        assert!(FeedVendor::custom("https://foo.test", Some("(")).is_err());
+    }
+}
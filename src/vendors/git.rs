@@ -0,0 +1,275 @@
+use crate::data;
+use crate::vendors::{http, TagFilter};
+use anyhow::anyhow;
+use anyhow::Result;
+use curl::easy::{Easy, List};
+use std::sync::MutexGuard;
+
+/// Tags refs prefix
+const TAGS_REF_PREFIX: &str = "refs/tags/";
+
+/// Git tags vendor
+///
+/// Check if there is a newer tag in any git repository served over HTTP (cgit, sourcehut, Bitbucket Server, ...),
+/// by reading the refs advertisement of the smart HTTP protocol (`info/refs?service=git-upload-pack`).
+/// The highest semver tag is used.
+///
+/// ## Usage Example
+/// ```
+/// use anyhow::Result;
+/// use upversion::vendors::GitTagsVendor;
+/// use upversion::CheckVersion;
+///
+/// fn main() -> Result<()> {
+///     let git = Box::new(GitTagsVendor::new("https://git.sr.ht/~owner/repo"));
+///     let timeout = 2; // in seconds
+///     let version_context = CheckVersion::new("app-name", git, timeout)?;
+///
+///     // run command execute upversion check in the background and finish immediately.
+///     version_context.run("0.0.1")?;
+///
+///     // your program logic here
+///
+///     // wait until the check is done, up to the given timeout
+///     version_context.wait(std::time::Duration::from_secs(3));
+///
+///     // at the end of your program, you can call printstd to print to the STDOUT a alert information for a new version which released
+///     version_context.printstd();
+///     Ok(())
+/// }
+/// ```
+pub struct GitTagsVendor {
+    repo_url: String,
+    tag_filter: Option<TagFilter>,
+}
+
+impl GitTagsVendor {
+    /// create git tags instance
+    ///
+    /// # Arguments
+    ///
+    /// * `repo_url` - repository clone URL
+    pub fn new(repo_url: &str) -> Self {
+        Self {
+            repo_url: repo_url.trim_end_matches('/').to_string(),
+            tag_filter: None,
+        }
+    }
+
+    /// Consider only the tags that matched the filter. The release version is the version extracted from the
+    /// tag (`cli-v1.2.3` -> `1.2.3`).
+    ///
+    /// # Arguments
+    ///
+    /// * `tag_filter` - tag prefix or regex
+    pub fn with_tag_filter(mut self, tag_filter: TagFilter) -> Self {
+        self.tag_filter = Some(tag_filter);
+        self
+    }
+
+    /// Parse the refs advertisement and return the refs names.
+    ///
+    /// Smart HTTP servers respond with pkt-line format (4 hex digits length prefix per line) starting with
+    /// the `# service=git-upload-pack` line, dumb HTTP servers respond with `<sha>\t<ref>` lines.
+    fn parse_refs(content: &[u8]) -> Result<Vec<String>> {
+        let lines = if content.get(4..14) == Some(b"# service=".as_slice()) {
+            Self::parse_pkt_lines(content)?
+        } else {
+            String::from_utf8_lossy(content)
+                .lines()
+                .map(|line| line.to_string())
+                .collect()
+        };
+
+        Ok(lines
+            .iter()
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| {
+                // the first ref line includes the server capabilities after NUL
+                let line = line.split('\0').next()?.trim_end();
+                let (_, name) = line.split_once([' ', '\t'])?;
+                Some(name.trim().to_string())
+            })
+            .collect())
+    }
+
+    fn pkt_line_length(content: &[u8]) -> Option<usize> {
+        let length = std::str::from_utf8(content.get(..4)?).ok()?;
+        usize::from_str_radix(length, 16).ok()
+    }
+
+    fn parse_pkt_lines(content: &[u8]) -> Result<Vec<String>> {
+        let mut lines = vec![];
+        let mut rest = content;
+        while !rest.is_empty() {
+            let length = match Self::pkt_line_length(rest) {
+                Some(l) => l,
+                None => return Err(anyhow!("invalid pkt-line length")),
+            };
+            // flush packet
+            if length == 0 {
+                rest = &rest[4..];
+                continue;
+            }
+            if length < 4 || length > rest.len() {
+                return Err(anyhow!("invalid pkt-line length: {}", length));
+            }
+            lines.push(String::from_utf8_lossy(&rest[4..length]).to_string());
+            rest = &rest[length..];
+        }
+        Ok(lines)
+    }
+}
+
+impl data::Vendor for GitTagsVendor {
+    fn id(&self) -> String {
+        match &self.tag_filter {
+            Some(filter) => format!("{}#{}", self.repo_url, filter.as_str()),
+            None => self.repo_url.clone(),
+        }
+    }
+
+    /// Get the highest semver tag
+    fn get(&self, client: MutexGuard<Easy>) -> Result<data::Release> {
+        let mut client = client;
+
+        let url = format!("{}/info/refs?service=git-upload-pack", self.repo_url);
+        let response = http::get(&mut client, &url, List::new())?.error_for_status()?;
+
+        let filter = self.tag_filter.clone().unwrap_or_default();
+        let tag = Self::parse_refs(&response.body)?
+            .into_iter()
+            .filter_map(|name| name.strip_prefix(TAGS_REF_PREFIX).map(|t| t.to_string()))
+            // annotated tags are advertised twice, the peeled ref point to the tagged commit
+            .filter(|tag| !tag.ends_with("^{}"))
+            .filter_map(|tag| filter.version(&tag).map(|version| (version, tag)))
+            .filter(|(version, _)| version.pre.is_empty())
+            .max_by(|(a, _), (b, _)| a.cmp(b));
+
+        match tag {
+            Some((version, tag)) => Ok(data::Release {
+                version: match self.tag_filter {
+                    Some(_) => version.to_string(),
+                    None => tag,
+                },
+                downloads_releases: vec![],
+                download_link: None,
+                install_command: None,
+                source: None,
+            }),
+            None => Err(anyhow!("semver tags not found")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod vendor_git_test {
+    use crate::data::Vendor;
+
+    use super::{Easy, GitTagsVendor};
+    use crate::vendors::TagFilter;
+    use insta::assert_debug_snapshot;
+    use std::sync::{Arc, Mutex};
+
+    /// Encode lines as pkt-line, the same as `git http-backend` response
+    fn pkt_lines(lines: &[&str]) -> String {
+        lines
+            .iter()
+            .map(|line| {
+                if line.is_empty() {
+                    return "0000".to_string();
+                }
+                format!("{:04x}{}", line.len() + 4, line)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn can_get_release_details() {
+        let url = &mockito::server_url();
+
+        let data = pkt_lines(&[
+            "# service=git-upload-pack\n",
+            "",
+            "1111111111111111111111111111111111111111 HEAD\0multi_ack thin-pack side-band symref=HEAD:refs/heads/main\n",
+            "1111111111111111111111111111111111111111 refs/heads/main\n",
+            "2222222222222222222222222222222222222222 refs/tags/v0.9.0\n",
+            "3333333333333333333333333333333333333333 refs/tags/v1.2.0\n",
+            "4444444444444444444444444444444444444444 refs/tags/v1.2.0^{}\n",
+            "5555555555555555555555555555555555555555 refs/tags/v2.0.0-rc.1\n",
+            "6666666666666666666666666666666666666666 refs/tags/nightly\n",
+            "",
+        ]);
+
+        let _m = mockito::mock("GET", "/repo.git/info/refs?service=git-upload-pack")
+            .with_header(
+                "content-type",
+                "application/x-git-upload-pack-advertisement",
+            )
+            .with_body(data)
+            .with_status(200)
+            .create();
+
+        let git = GitTagsVendor::new(format!("{}/repo.git", url).as_str());
+
+        let easy = Easy::new();
+        assert_debug_snapshot!(git.get(Arc::new(Mutex::new(easy)).lock().unwrap()));
+    }
+
+    #[test]
+    fn can_get_release_details_from_dumb_server() {
+        let url = &mockito::server_url();
+
+        let data = "2222222222222222222222222222222222222222\trefs/tags/1.0.0\n3333333333333333333333333333333333333333\trefs/tags/1.1.0\n";
+
+        let _m = mockito::mock("GET", "/repo.git/info/refs?service=git-upload-pack")
+            .with_body(data)
+            .with_status(200)
+            .create();
+
+        let git = GitTagsVendor::new(format!("{}/repo.git", url).as_str());
+
+        let easy = Easy::new();
+        assert_debug_snapshot!(git.get(Arc::new(Mutex::new(easy)).lock().unwrap()));
+    }
+
+    #[test]
+    fn can_get_release_details_without_tags() {
+        let url = &mockito::server_url();
+
+        let data = pkt_lines(&[
+            "# service=git-upload-pack\n",
+            "",
+            "0000000000000000000000000000000000000000 capabilities^{}\0multi_ack\n",
+            "",
+        ]);
+
+        let _m = mockito::mock("GET", "/repo.git/info/refs?service=git-upload-pack")
+            .with_body(data)
+            .with_status(200)
+            .create();
+
+        let git = GitTagsVendor::new(format!("{}/repo.git", url).as_str());
+
+        let easy = Easy::new();
+        assert_debug_snapshot!(git.get(Arc::new(Mutex::new(easy)).lock().unwrap()));
+    }
+
+    #[test]
+    fn can_filter_monorepo_tags() {
+        let url = &mockito::server_url();
+
+        let data = "1111111111111111111111111111111111111111\trefs/tags/cli-v1.0.0\n2222222222222222222222222222222222222222\trefs/tags/cli-v1.1.0\n3333333333333333333333333333333333333333\trefs/tags/agent-v4.0.0\n";
+
+        let _m = mockito::mock("GET", "/repo.git/info/refs?service=git-upload-pack")
+            .with_body(data)
+            .with_status(200)
+            .create();
+
+        let git = GitTagsVendor::new(format!("{}/repo.git", url).as_str())
+            .with_tag_filter(TagFilter::prefix("cli-"));
+
+        let easy = Easy::new();
+        assert_debug_snapshot!(git.get(Arc::new(Mutex::new(easy)).lock().unwrap()));
+    }
+}
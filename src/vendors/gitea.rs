@@ -0,0 +1,278 @@
+use crate::data;
+use crate::vendors::github::ReleasesResponse;
+use crate::vendors::{http, TagFilter};
+use anyhow::anyhow;
+use anyhow::Result;
+use curl::easy::{Easy, List};
+use std::sync::MutexGuard;
+
+/// Default Gitea base URL
+const DEFAULT_GITEA_URL: &str = "https://codeberg.org";
+/// Number of releases to look for a matched tag, when tag filter is used (Gitea max page size)
+const FILTERED_RELEASES_PER_PAGE: usize = 50;
+
+/// Gitea vendor
+///
+/// Check if there is a new version from the latest release of Gitea compatible forges (Gitea, Forgejo, Codeberg).
+///
+/// ## Usage Example
+/// ```
+/// use anyhow::Result;
+/// use upversion::vendors::GiteaVendor;
+/// use upversion::CheckVersion;
+///
+/// fn main() -> Result<()> {
+///     let gitea = Box::new(GiteaVendor::new("owner", "repo"));
+///     let timeout = 2; // in seconds
+///     let version_context = CheckVersion::new("app-name", gitea, timeout)?;
+///
+///     // run command execute upversion check in the background and finish immediately.
+///     version_context.run("0.0.1")?;
+///
+///     // your program logic here
+///
+///     // wait until the check is done, up to the given timeout
+///     version_context.wait(std::time::Duration::from_secs(3));
+///
+///     // at the end of your program, you can call printstd to print to the STDOUT a alert information for a new version which released
+///     version_context.printstd();
+///     Ok(())
+/// }
+/// ```
+pub struct GiteaVendor {
+    base_url: String,
+    owner: String,
+    repo: String,
+    token: Option<String>,
+    tag_filter: Option<TagFilter>,
+}
+
+impl GiteaVendor {
+    /// create Gitea instance for a Codeberg repository
+    ///
+    /// # Arguments
+    ///
+    /// * `owner` - repository owner/organization
+    /// * `repo` - repository name
+    pub fn new(owner: &str, repo: &str) -> Self {
+        Self::custom(owner, repo, None, None)
+    }
+
+    /// create Gitea instance
+    ///
+    /// # Arguments
+    ///
+    /// * `owner` - repository owner/organization
+    /// * `repo` - repository name
+    /// * `base_url` - Gitea/Forgejo instance URL
+    /// * `token` - access token for private repositories
+    ///
+    pub fn custom(
+        owner: &str,
+        repo: &str,
+        base_url: Option<String>,
+        token: Option<String>,
+    ) -> Self {
+        Self {
+            base_url: base_url
+                .unwrap_or_else(|| DEFAULT_GITEA_URL.to_string())
+                .trim_end_matches('/')
+                .to_string(),
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+            token,
+            tag_filter: None,
+        }
+    }
+
+    /// Consider only the releases with tags that matched the filter, the highest stable version of the latest
+    /// 50 releases is used. The release version is the version extracted from the tag (`cli-v1.2.3` -> `1.2.3`).
+    ///
+    /// # Arguments
+    ///
+    /// * `tag_filter` - tag prefix or regex
+    pub fn with_tag_filter(mut self, tag_filter: TagFilter) -> Self {
+        self.tag_filter = Some(tag_filter);
+        self
+    }
+
+    /// Get the highest stable release that matched the tag filter
+    fn filtered(&self, client: &mut Easy, filter: &TagFilter) -> Result<data::Release> {
+        let url = format!(
+            "{}/api/v1/repos/{}/{}/releases?limit={}",
+            self.base_url, self.owner, self.repo, FILTERED_RELEASES_PER_PAGE
+        );
+
+        let response = http::get(client, &url, self.headers()?)?.error_for_status()?;
+        let response: Vec<ReleasesResponse> = serde_json::from_slice(&response.body)?;
+
+        let release = response
+            .into_iter()
+            .filter(|release| !release.draft && !release.prerelease)
+            .filter_map(|release| {
+                filter
+                    .version(&release.tag_name)
+                    .map(|version| (version, release))
+            })
+            .filter(|(version, _)| version.pre.is_empty())
+            .max_by(|(a, _), (b, _)| a.cmp(b));
+
+        match release {
+            Some((version, release)) => Ok(data::Release {
+                version: version.to_string(),
+                ..release.to_release()
+            }),
+            None => Err(anyhow!("releases not found")),
+        }
+    }
+
+    fn headers(&self) -> Result<List> {
+        let mut headers = List::new();
+        headers.append("accept: application/json")?;
+        if let Some(token) = &self.token {
+            headers.append(format!("authorization: token {}", token).as_str())?;
+        }
+        Ok(headers)
+    }
+}
+
+impl data::Vendor for GiteaVendor {
+    fn id(&self) -> String {
+        match &self.tag_filter {
+            Some(filter) => format!(
+                "{}/repos/{}/{}#{}",
+                self.base_url,
+                self.owner,
+                self.repo,
+                filter.as_str()
+            ),
+            None => format!("{}/repos/{}/{}", self.base_url, self.owner, self.repo),
+        }
+    }
+
+    /// Get latest release version
+    fn get(&self, client: MutexGuard<Easy>) -> Result<data::Release> {
+        let mut client = client;
+
+        if let Some(filter) = &self.tag_filter {
+            return self.filtered(&mut client, filter);
+        }
+
+        let url = format!(
+            "{}/api/v1/repos/{}/{}/releases/latest",
+            self.base_url, self.owner, self.repo
+        );
+
+        let response = http::get(&mut client, &url, self.headers()?)?.error_for_status()?;
+        let response: ReleasesResponse = serde_json::from_slice(&response.body)?;
+
+        Ok(response.to_release())
+    }
+}
+
+#[cfg(test)]
+mod vendor_gitea_test {
+    use crate::data::Vendor;
+
+    use super::{Easy, GiteaVendor};
+    use crate::vendors::TagFilter;
+    use insta::assert_debug_snapshot;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn can_get_release_details() {
+        let url = &mockito::server_url();
+
+        let gitea = GiteaVendor::custom("owner", "repo", Some(url.to_string()), None);
+
+        let data = r#"
+        {
+            "id": 1,
+            "tag_name": "v0.1.6",
+            "draft": false,
+            "prerelease": false,
+            "assets": [
+                {
+                    "name": "foo",
+                    "browser_download_url": "https://codeberg.org/foo"
+                },
+                {
+                    "name": "bar",
+                    "browser_download_url": "https://codeberg.org/bar"
+                }
+            ]
+        }
+        "#;
+
+        let _m = mockito::mock("GET", "/api/v1/repos/owner/repo/releases/latest")
+            .match_header("accept", "application/json")
+            .with_body(data)
+            .with_status(200)
+            .create();
+
+        let easy = Easy::new();
+        assert_debug_snapshot!(gitea.get(Arc::new(Mutex::new(easy)).lock().unwrap()));
+    }
+
+    #[test]
+    fn can_get_release_details_with_token() {
+        let url = &mockito::server_url();
+
+        let gitea = GiteaVendor::custom(
+            "owner",
+            "repo",
+            Some(url.to_string()),
+            Some("secret".to_string()),
+        );
+
+        let _m = mockito::mock("GET", "/api/v1/repos/owner/repo/releases/latest")
+            .match_header("authorization", "token secret")
+            .with_body(r#"{"tag_name": "1.0.0", "assets": []}"#)
+            .with_status(200)
+            .create();
+
+        let easy = Easy::new();
+        assert_debug_snapshot!(gitea.get(Arc::new(Mutex::new(easy)).lock().unwrap()));
+    }
+
+    #[test]
+    fn can_get_release_details_with_tag_filter() {
+        let url = &mockito::server_url();
+
+        let gitea = GiteaVendor::custom("owner", "repo", Some(url.to_string()), None)
+            .with_tag_filter(TagFilter::prefix("cli-"));
+
+        let data = r#"
+        [
+            {"tag_name": "agent-v3.0.0", "assets": []},
+            {"tag_name": "cli-v1.3.0", "prerelease": true, "assets": []},
+            {"tag_name": "cli-v1.2.0", "assets": [{"browser_download_url": "https://codeberg.org/cli"}]},
+            {"tag_name": "cli-v1.1.0", "assets": []}
+        ]
+        "#;
+
+        let _m = mockito::mock("GET", "/api/v1/repos/owner/repo/releases?limit=50")
+            .with_body(data)
+            .with_status(200)
+            .create();
+
+        let easy = Easy::new();
+        assert_debug_snapshot!(gitea.get(Arc::new(Mutex::new(easy)).lock().unwrap()));
+        assert_debug_snapshot!(gitea.id());
+    }
+
+    #[test]
+    fn can_get_release_details_without_releases() {
+        let url = &mockito::server_url();
+
+        let gitea = GiteaVendor::custom("owner", "repo", Some(url.to_string()), None);
+
+        let _m = mockito::mock("GET", "/api/v1/repos/owner/repo/releases/latest")
+            .with_body(r#"{"errors":null,"message":"not found"}"#)
+            .with_status(404)
+            .create();
+
+        let easy = Easy::new();
+        assert_debug_snapshot!(gitea.get(Arc::new(Mutex::new(easy)).lock().unwrap()));
+    }
+}
@@ -1,30 +1,71 @@
 use crate::data;
+use crate::vendors::{http, TagFilter};
 use anyhow::anyhow;
 use anyhow::Result;
 use curl::easy::{Easy, List};
 use serde::{Deserialize, Serialize};
+use std::env;
 use std::sync::MutexGuard;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 /// Default GitHub base URL
 const DEFAULT_GITHUB_URL: &str = "https://api.github.com";
+/// Environment variables to read the GitHub token from, by order
+const GITHUB_TOKEN_ENV_VARS: &[&str] = &["GITHUB_TOKEN", "GH_TOKEN"];
+/// Number of releases per page. The releases response includes the release notes, a small page keeps the
+/// common case (the newest releases include a published one) fast
+const RELEASES_PER_PAGE: usize = 10;
+/// Maximum number of releases pages to go through
+const MAX_RELEASES_PAGES: usize = 10;
+/// Wait time when the secondary rate limit is exceeded without a reset time (in seconds)
+const DEFAULT_RETRY_AFTER: u64 = 60;
 
 #[derive(Deserialize, Serialize, Debug)]
-struct ReleasesResponse {
+pub(crate) struct ReleasesResponse {
     #[serde(rename = "tag_name")]
-    tag_name: String,
+    pub(crate) tag_name: String,
     #[serde(rename = "assets")]
     assets: Vec<ReleaseAssetResponse>,
+    #[serde(default)]
+    pub(crate) draft: bool,
+    #[serde(default)]
+    pub(crate) prerelease: bool,
 }
 
 #[derive(Deserialize, Serialize, Debug)]
-struct ReleaseAssetResponse {
+pub(crate) struct ReleaseAssetResponse {
     #[serde(rename = "browser_download_url")]
     browser_download_url: String,
 }
 
+impl ReleasesResponse {
+    /// Convert the release response to `Release` with the release assets as download links.
+    ///
+    /// Shared with the vendors that return GitHub compatible releases response.
+    pub(crate) fn to_release(&self) -> data::Release {
+        let download_releases = self
+            .assets
+            .iter()
+            .map(|asset| asset.browser_download_url.to_string())
+            .collect::<Vec<_>>();
+
+        data::Release {
+            version: self.tag_name.to_string(),
+            downloads_releases: download_releases,
+            download_link: None,
+            install_command: None,
+            source: None,
+        }
+    }
+}
+
 /// GitHub vendor
 ///
-/// Check if there is a new version from releases page
+/// Check if there is a new version from releases page.
+///
+/// The releases are requested in small pages (newest first), and further pages are requested only until a page
+/// with a published semver release is found. The highest version of that page is used. Drafts and pre-releases
+/// are skipped unless pre-releases are enabled with [`GitHubVendor::with_prereleases`].
 ///
 /// ## Usage Example
 /// ```
@@ -40,18 +81,48 @@ struct ReleaseAssetResponse {
 ///     // run command execute upversion check in the background and finish immediately.
 ///     version_context.run("0.0.1")?;
 ///
-///     // sleep here simulator your program
-///     std::thread::sleep(std::time::Duration::from_secs(3));
+///     // your program logic here
+///
+///     // wait until the check is done, up to the given timeout
+///     version_context.wait(std::time::Duration::from_secs(3));
 ///
 ///     // at the end of your program, you can call printstd to print to the STDOUT a alert information for a new version which released
 ///     version_context.printstd();
 ///     Ok(())
 /// }
 /// ```
+///
+/// ## Authentication
+/// Unauthenticated requests are limited to 60 requests per hour per IP, authenticated requests have a much higher limit.
+/// ```
+/// use upversion::vendors::GitHubVendor;
+///
+/// // explicit token
+/// let github = GitHubVendor::new("owner", "repo").with_token("ghp_xxx");
+/// // token from `GITHUB_TOKEN` or `GH_TOKEN` environment variables (when set)
+/// let github = GitHubVendor::new("owner", "repo").with_env_token();
+/// ```
+///
+/// ## Release channels
+/// ```
+/// use upversion::vendors::GitHubVendor;
+///
+/// // consider pre-releases (`1.0.0-rc.1`) as well
+/// let github = GitHubVendor::new("owner", "repo").with_prereleases();
+/// // use the release that marked as latest in GitHub (single request, without paging)
+/// let github = GitHubVendor::new("owner", "repo").with_latest_endpoint();
+/// ```
+///
+/// ## Monorepo
+/// Use [`TagFilter`] to consider only the releases of a single component, see [`GitHubVendor::with_tag_filter`].
 pub struct GitHubVendor {
     base_url: String,
     owner: String,
     repo: String,
+    token: Option<String>,
+    prereleases: bool,
+    latest_endpoint: bool,
+    tag_filter: Option<TagFilter>,
 }
 
 impl GitHubVendor {
@@ -78,59 +149,249 @@ impl GitHubVendor {
             base_url: base_url.unwrap_or_else(|| DEFAULT_GITHUB_URL.to_string()),
             owner: owner.to_string(),
             repo: repo.to_string(),
+            token: None,
+            prereleases: false,
+            latest_endpoint: false,
+            tag_filter: None,
         }
     }
 
+    /// Authenticate the requests with the given token
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - GitHub personal access token
+    pub fn with_token(mut self, token: &str) -> Self {
+        self.token = Some(token.to_string());
+        self
+    }
+
+    /// Authenticate the requests with the token from `GITHUB_TOKEN` or `GH_TOKEN` environment variables.
+    /// When none of the variables is set the requests are unauthenticated.
+    pub fn with_env_token(mut self) -> Self {
+        self.token = GITHUB_TOKEN_ENV_VARS
+            .iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|token| !token.trim().is_empty());
+        self
+    }
+
+    /// Consider pre-releases when looking for the newest release
+    pub fn with_prereleases(mut self) -> Self {
+        self.prereleases = true;
+        self
+    }
+
+    /// Use the `/releases/latest` endpoint, which returns the most recent published non pre-release release.
+    /// Ignored when pre-releases or tag filter are enabled.
+    pub fn with_latest_endpoint(mut self) -> Self {
+        self.latest_endpoint = true;
+        self
+    }
+
+    /// Consider only the releases with tags that matched the filter. The release version is the version extracted
+    /// from the tag (`cli-v1.2.3` -> `1.2.3`).
+    ///
+    /// # Arguments
+    ///
+    /// * `tag_filter` - tag prefix or regex
+    pub fn with_tag_filter(mut self, tag_filter: TagFilter) -> Self {
+        self.tag_filter = Some(tag_filter);
+        self
+    }
+
     fn default_headers() -> Result<List> {
         let mut headers = List::new();
         headers.append("accept: application/vnd.github.v3+json")?;
         Ok(headers)
     }
-}
 
-impl data::Vendor for GitHubVendor {
-    /// Get latest release version
-    fn get(&self, client: MutexGuard<Easy>) -> Result<data::Release> {
-        let mut json = Vec::new();
-        let mut client = client;
+    fn headers(&self) -> Result<List> {
+        let mut headers = Self::default_headers()?;
+        if let Some(token) = &self.token {
+            headers.append(format!("authorization: Bearer {}", token.trim()).as_str())?;
+        }
+        Ok(headers)
+    }
+
+    /// Return the rate limit details when the response is rate limited.
+    ///
+    /// `Retry-After` header (secondary rate limit) take precedence over `X-RateLimit-Reset` (primary rate limit).
+    fn rate_limited(response: &http::Response) -> Option<data::RateLimited> {
+        if response.status != 403 && response.status != 429 {
+            return None;
+        }
+
+        let retry_after = response
+            .header("retry-after")
+            .and_then(|v| v.trim().parse::<u64>().ok());
+        if let Some(retry_after) = retry_after {
+            return Some(data::RateLimited {
+                until: SystemTime::now() + Duration::from_secs(retry_after),
+            });
+        }
+
+        if response.header("x-ratelimit-remaining").map(str::trim) == Some("0") {
+            let reset = response
+                .header("x-ratelimit-reset")
+                .and_then(|v| v.trim().parse::<u64>().ok());
+            if let Some(reset) = reset {
+                return Some(data::RateLimited {
+                    until: UNIX_EPOCH + Duration::from_secs(reset),
+                });
+            }
+        }
+
+        if response.status == 429 {
+            return Some(data::RateLimited {
+                until: SystemTime::now() + Duration::from_secs(DEFAULT_RETRY_AFTER),
+            });
+        }
+        None
+    }
+
+    /// Perform the request, `304 Not Modified` response is returned only for conditional request
+    fn request(
+        &self,
+        client: &mut Easy,
+        url: &str,
+        cached: Option<&data::CachedRelease>,
+    ) -> Result<http::Response> {
+        let mut headers = self.headers()?;
+        http::append_conditional_headers(&mut headers, cached)?;
+
+        let response = http::get(client, url, headers)?;
+        if let Some(rate_limited) = Self::rate_limited(&response) {
+            return Err(rate_limited.into());
+        }
+        if cached.is_some() && response.is_not_modified() {
+            return Ok(response);
+        }
+        response.error_for_status()
+    }
 
+    fn is_supported(&self, release: &ReleasesResponse) -> bool {
+        !release.draft && (self.prereleases || !release.prerelease)
+    }
+
+    /// Select the highest semver release of the page
+    fn select<'a>(
+        &self,
+        releases: &'a [ReleasesResponse],
+        filter: &TagFilter,
+    ) -> Option<&'a ReleasesResponse> {
+        releases
+            .iter()
+            .filter(|release| self.is_supported(release))
+            .filter_map(|release| {
+                filter
+                    .version(&release.tag_name)
+                    .map(|version| (version, release))
+            })
+            .filter(|(version, _)| self.prereleases || version.pre.is_empty())
+            .max_by(|(a, _), (b, _)| a.cmp(b))
+            .map(|(_, release)| release)
+    }
+
+    fn to_release(&self, release: &ReleasesResponse, filter: &TagFilter) -> data::Release {
+        let version = match (&self.tag_filter, filter.version(&release.tag_name)) {
+            (Some(_), Some(version)) => version.to_string(),
+            _ => release.tag_name.to_string(),
+        };
+        data::Release {
+            version,
+            ..release.to_release()
+        }
+    }
+
+    fn latest(
+        &self,
+        client: &mut Easy,
+        cached: Option<&data::CachedRelease>,
+    ) -> Result<data::CachedRelease> {
         let url = format!(
-            "{}/repos/{}/{}/releases?per_page=1",
+            "{}/repos/{}/{}/releases/latest",
             self.base_url, self.owner, self.repo
         );
-        client.url(&url)?;
 
-        client.http_headers(Self::default_headers()?)?;
-        {
-            let mut transfer = client.transfer();
-            transfer
-                .write_function(|data| {
-                    json.extend_from_slice(data);
-                    Ok(data.len())
-                })
-                .unwrap();
-            transfer.perform()?;
+        let response = self.request(client, &url, cached)?;
+        if let (true, Some(cached)) = (response.is_not_modified(), cached) {
+            return Ok(cached.clone());
         }
+        let release: ReleasesResponse = serde_json::from_slice(&response.body)?;
+        Ok(data::CachedRelease {
+            release: release.to_release(),
+            validators: response.validators(),
+        })
+    }
+}
+
+impl data::Vendor for GitHubVendor {
+    fn id(&self) -> String {
+        let mut id = format!("{}/repos/{}/{}", self.base_url, self.owner, self.repo);
+        if self.prereleases {
+            id.push_str("#prereleases");
+        } else if self.latest_endpoint && self.tag_filter.is_none() {
+            id.push_str("#latest");
+        }
+        if let Some(filter) = &self.tag_filter {
+            id.push_str(&format!("#{}", filter.as_str()));
+        }
+        id
+    }
 
-        let response: Vec<ReleasesResponse> = serde_json::from_slice(&json)?;
+    /// Get latest release version
+    fn get(&self, client: MutexGuard<Easy>) -> Result<data::Release> {
+        Ok(self.get_conditional(client, None)?.release)
+    }
 
-        if response.is_empty() {
-            return Err(anyhow!("releases not found"));
+    /// Get latest release version with a conditional request of the first releases page.
+    /// Not modified responses are not counted against the GitHub rate limit.
+    fn get_conditional(
+        &self,
+        client: MutexGuard<Easy>,
+        cached: Option<&data::CachedRelease>,
+    ) -> Result<data::CachedRelease> {
+        let mut client = client;
+
+        if self.latest_endpoint && !self.prereleases && self.tag_filter.is_none() {
+            return self.latest(&mut client, cached);
         }
 
-        // github request limited to 1 item response (see request quey parameter).
-        let release_details = response.first().unwrap();
+        let filter = self.tag_filter.clone().unwrap_or_default();
+        let mut validators = data::CacheValidators::default();
+        for page in 1..=MAX_RELEASES_PAGES {
+            let url = format!(
+                "{}/repos/{}/{}/releases?per_page={}&page={}",
+                self.base_url, self.owner, self.repo, RELEASES_PER_PAGE, page
+            );
 
-        let download_releases = &release_details
-            .assets
-            .iter()
-            .map(|asset| asset.browser_download_url.to_string())
-            .collect::<Vec<_>>();
+            // the releases are sorted by date, when the first page is not modified the selected release is the same
+            let response = if page == 1 {
+                let response = self.request(&mut client, &url, cached)?;
+                if let (true, Some(cached)) = (response.is_not_modified(), cached) {
+                    return Ok(cached.clone());
+                }
+                validators = response.validators();
+                response
+            } else {
+                self.request(&mut client, &url, None)?
+            };
+            let releases: Vec<ReleasesResponse> = serde_json::from_slice(&response.body)?;
 
-        Ok(data::Release {
-            version: release_details.tag_name.to_string(),
-            downloads_releases: download_releases.clone(),
-        })
+            if let Some(release) = self.select(&releases, &filter) {
+                return Ok(data::CachedRelease {
+                    release: self.to_release(release, &filter),
+                    validators,
+                });
+            }
+
+            if releases.len() < RELEASES_PER_PAGE {
+                break;
+            }
+        }
+
+        Err(anyhow!("releases not found"))
     }
 }
 
@@ -139,8 +400,11 @@ mod vendor_github_github {
     use crate::data::Vendor;
 
     use super::{Easy, GitHubVendor};
+    use crate::data::RateLimited;
+    use crate::vendors::TagFilter;
     use insta::assert_debug_snapshot;
     use std::sync::{Arc, Mutex};
+    use std::time::SystemTime;
 
     #[test]
     fn can_get_release_details() {
@@ -163,7 +427,7 @@ mod vendor_github_github {
         ]
         "#;
 
-        let _m = mockito::mock("GET", "/repos/owner/repo/releases?per_page=1")
+        let _m = mockito::mock("GET", "/repos/owner/repo/releases?per_page=10&page=1")
             .match_header("accept", "application/vnd.github.v3+json")
             .with_body(data)
             .with_status(200)
@@ -179,7 +443,7 @@ mod vendor_github_github {
 
         let github = GitHubVendor::custom("owner", "repo", Some(url.to_string()));
 
-        let _m = mockito::mock("GET", "/repos/owner/repo/releases?per_page=1")
+        let _m = mockito::mock("GET", "/repos/owner/repo/releases?per_page=10&page=1")
             .match_header("accept", "application/vnd.github.v3+json")
             .with_body("[]")
             .with_status(200)
@@ -188,4 +452,220 @@ mod vendor_github_github {
         let easy = Easy::new();
         assert_debug_snapshot!(github.get(Arc::new(Mutex::new(easy)).lock().unwrap()));
     }
+
+    #[test]
+    fn can_get_release_details_with_token() {
+        let url = &mockito::server_url();
+
+        let github =
+            GitHubVendor::custom("owner", "repo", Some(url.to_string())).with_token("secret");
+
+        let _m = mockito::mock("GET", "/repos/owner/repo/releases?per_page=10&page=1")
+            .match_header("accept", "application/vnd.github.v3+json")
+            .match_header("authorization", "Bearer secret")
+            .with_body(r#"[{"tag_name": "v0.1.6", "assets": []}]"#)
+            .with_status(200)
+            .create();
+
+        let easy = Easy::new();
+        assert_debug_snapshot!(github.get(Arc::new(Mutex::new(easy)).lock().unwrap()));
+    }
+
+    #[test]
+    fn can_get_rate_limit_reset() {
+        let url = &mockito::server_url();
+
+        let github = GitHubVendor::custom("owner", "repo", Some(url.to_string()));
+
+        let _m = mockito::mock("GET", "/repos/owner/repo/releases?per_page=10&page=1")
+            .with_header("x-ratelimit-remaining", "0")
+            .with_header("x-ratelimit-reset", "4102444800")
+            .with_body(r#"{"message": "API rate limit exceeded"}"#)
+            .with_status(403)
+            .create();
+
+        let easy = Easy::new();
+        let err = github
+            .get(Arc::new(Mutex::new(easy)).lock().unwrap())
+            .unwrap_err();
+        assert_debug_snapshot!(err.downcast_ref::<RateLimited>().map(|r| r.until_secs()));
+    }
+
+    #[test]
+    fn can_get_rate_limit_retry_after() {
+        let url = &mockito::server_url();
+
+        let github = GitHubVendor::custom("owner", "repo", Some(url.to_string()));
+
+        let _m = mockito::mock("GET", "/repos/owner/repo/releases?per_page=10&page=1")
+            .with_header("retry-after", "120")
+            .with_body(r#"{"message": "You have exceeded a secondary rate limit"}"#)
+            .with_status(403)
+            .create();
+
+        let easy = Easy::new();
+        let err = github
+            .get(Arc::new(Mutex::new(easy)).lock().unwrap())
+            .unwrap_err();
+        let rate_limited = err.downcast_ref::<RateLimited>().unwrap();
+        assert!(rate_limited.until > SystemTime::now());
+    }
+
+    #[test]
+    fn can_get_error_status() {
+        let url = &mockito::server_url();
+
+        let github = GitHubVendor::custom("owner", "repo", Some(url.to_string()));
+
+        let _m = mockito::mock("GET", "/repos/owner/repo/releases?per_page=10&page=1")
+            .with_body(r#"{"message": "Not Found"}"#)
+            .with_status(404)
+            .create();
+
+        let easy = Easy::new();
+        assert_debug_snapshot!(github.get(Arc::new(Mutex::new(easy)).lock().unwrap()));
+    }
+
+    #[test]
+    fn can_skip_drafts_and_prereleases() {
+        let url = &mockito::server_url();
+
+        let data = r#"[
+            {"tag_name": "v2.0.0", "draft": true, "prerelease": false, "assets": []},
+            {"tag_name": "v1.3.0-rc.1", "draft": false, "prerelease": true, "assets": []},
+            {"tag_name": "nightly", "draft": false, "prerelease": false, "assets": []},
+            {"tag_name": "v1.1.1", "draft": false, "prerelease": false, "assets": []},
+            {"tag_name": "v1.2.0", "draft": false, "prerelease": false, "assets": []}
+        ]"#;
+
+        let _m = mockito::mock("GET", "/repos/owner/repo/releases?per_page=10&page=1")
+            .with_body(data)
+            .with_status(200)
+            .create();
+
+        let github = GitHubVendor::custom("owner", "repo", Some(url.to_string()));
+        let easy = Easy::new();
+        assert_debug_snapshot!(github.get(Arc::new(Mutex::new(easy)).lock().unwrap()));
+
+        let github =
+            GitHubVendor::custom("owner", "repo", Some(url.to_string())).with_prereleases();
+        let easy = Easy::new();
+        assert_debug_snapshot!(github.get(Arc::new(Mutex::new(easy)).lock().unwrap()));
+    }
+
+    #[test]
+    fn can_page_through_releases() {
+        let url = &mockito::server_url();
+
+        let page = (0..10)
+            .map(|i| {
+                format!(
+                    r#"{{"tag_name": "v3.0.0-beta.{}", "prerelease": true, "assets": []}}"#,
+                    i
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+
+        let _m1 = mockito::mock("GET", "/repos/owner/repo/releases?per_page=10&page=1")
+            .with_body(format!("[{}]", page))
+            .with_status(200)
+            .create();
+        let _m2 = mockito::mock("GET", "/repos/owner/repo/releases?per_page=10&page=2")
+            .with_body(r#"[{"tag_name": "v2.5.0", "assets": []}]"#)
+            .with_status(200)
+            .create();
+
+        let github = GitHubVendor::custom("owner", "repo", Some(url.to_string()));
+        let easy = Easy::new();
+        assert_debug_snapshot!(github.get(Arc::new(Mutex::new(easy)).lock().unwrap()));
+    }
+
+    #[test]
+    fn can_get_latest_release() {
+        let url = &mockito::server_url();
+
+        let _m = mockito::mock("GET", "/repos/owner/repo/releases/latest")
+            .with_body(
+                r#"{"tag_name": "v1.4.0", "draft": false, "prerelease": false, "assets": []}"#,
+            )
+            .with_status(200)
+            .create();
+
+        let github =
+            GitHubVendor::custom("owner", "repo", Some(url.to_string())).with_latest_endpoint();
+        let easy = Easy::new();
+        assert_debug_snapshot!(github.get(Arc::new(Mutex::new(easy)).lock().unwrap()));
+    }
+
+    #[test]
+    fn can_filter_monorepo_tags() {
+        let url = &mockito::server_url();
+
+        let data = r#"[
+            {"tag_name": "agent/2.0.0", "assets": []},
+            {"tag_name": "cli-v1.3.0", "assets": []},
+            {"tag_name": "cli-v1.2.0", "assets": []},
+            {"tag_name": "v5.0.0", "assets": []}
+        ]"#;
+
+        let _m = mockito::mock("GET", "/repos/owner/repo/releases?per_page=10&page=1")
+            .with_body(data)
+            .with_status(200)
+            .create();
+
+        let github = GitHubVendor::custom("owner", "repo", Some(url.to_string()))
+            .with_tag_filter(TagFilter::prefix("cli-"));
+        let easy = Easy::new();
+        assert_debug_snapshot!(github.get(Arc::new(Mutex::new(easy)).lock().unwrap()));
+
+        let github = GitHubVendor::custom("owner", "repo", Some(url.to_string()))
+            .with_tag_filter(TagFilter::regex(r"^agent/(?P<version>.+)$").unwrap());
+        let easy = Easy::new();
+        assert_debug_snapshot!(github.get(Arc::new(Mutex::new(easy)).lock().unwrap()));
+    }
+
+    #[test]
+    fn can_get_cached_release_when_not_modified() {
+        let url = &mockito::server_url();
+
+        let github = GitHubVendor::custom("owner", "repo", Some(url.to_string()));
+
+        let m = mockito::mock("GET", "/repos/owner/repo/releases?per_page=10&page=1")
+            .with_header("etag", "W/\"abc\"")
+            .with_header("last-modified", "Tue, 01 Nov 2022 10:00:00 GMT")
+            .with_body(r#"[{"tag_name": "v0.1.6", "assets": []}]"#)
+            .with_status(200)
+            .create();
+
+        let easy = Easy::new();
+        let cached = github
+            .get_conditional(Arc::new(Mutex::new(easy)).lock().unwrap(), None)
+            .unwrap();
+        assert_debug_snapshot!(cached);
+        drop(m);
+
+        let _m = mockito::mock("GET", "/repos/owner/repo/releases?per_page=10&page=1")
+            .match_header("if-none-match", "W/\"abc\"")
+            .match_header("if-modified-since", "Tue, 01 Nov 2022 10:00:00 GMT")
+            .with_header("x-ratelimit-remaining", "0")
+            .with_status(304)
+            .create();
+
+        let easy = Easy::new();
+        assert_debug_snapshot!(
+            github.get_conditional(Arc::new(Mutex::new(easy)).lock().unwrap(), Some(&cached))
+        );
+    }
+
+    #[test]
+    fn can_get_id() {
+        let github = GitHubVendor::new("owner", "repo").with_token("secret");
+        assert_debug_snapshot!(github.id());
+
+        let github = GitHubVendor::new("owner", "repo")
+            .with_prereleases()
+            .with_tag_filter(TagFilter::prefix("cli-"));
+        assert_debug_snapshot!(github.id());
+    }
 }
@@ -0,0 +1,317 @@
+use crate::data;
+use crate::vendors::{http, TagFilter};
+use anyhow::anyhow;
+use anyhow::Result;
+use curl::easy::{Easy, List};
+use serde::{Deserialize, Serialize};
+use std::sync::MutexGuard;
+
+/// Default GitLab base URL
+const DEFAULT_GITLAB_URL: &str = "https://gitlab.com";
+/// Number of releases to look for a matched tag, when tag filter is used
+const FILTERED_RELEASES_PER_PAGE: usize = 100;
+
+#[derive(Deserialize, Serialize, Debug)]
+struct ReleasesResponse {
+    tag_name: String,
+    assets: ReleaseAssetsResponse,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+struct ReleaseAssetsResponse {
+    #[serde(default)]
+    links: Vec<ReleaseAssetLinkResponse>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+struct ReleaseAssetLinkResponse {
+    url: String,
+}
+
+/// GitLab vendor
+///
+/// Check if there is a new version from the project releases
+///
+/// ## Usage Example
+/// ```
+/// use anyhow::Result;
+/// use upversion::vendors::GitLabVendor;
+/// use upversion::CheckVersion;
+///
+/// fn main() -> Result<()> {
+///     let gitlab = Box::new(GitLabVendor::new("group/project"));
+///     let timeout = 2; // in seconds
+///     let version_context = CheckVersion::new("app-name", gitlab, timeout)?;
+///
+///     // run command execute upversion check in the background and finish immediately.
+///     version_context.run("0.0.1")?;
+///
+///     // your program logic here
+///
+///     // wait until the check is done, up to the given timeout
+///     version_context.wait(std::time::Duration::from_secs(3));
+///
+///     // at the end of your program, you can call printstd to print to the STDOUT a alert information for a new version which released
+///     version_context.printstd();
+///     Ok(())
+/// }
+/// ```
+pub struct GitLabVendor {
+    base_url: String,
+    project: String,
+    tag_filter: Option<TagFilter>,
+}
+
+impl GitLabVendor {
+    /// create GitLab instance
+    ///
+    /// # Arguments
+    ///
+    /// * `project` - GitLab numeric project id or project path (`group/project`)
+    pub fn new(project: &str) -> Self {
+        Self::custom(project, None)
+    }
+
+    /// create GitLab instance
+    ///
+    /// # Arguments
+    ///
+    /// * `project` - GitLab numeric project id or project path (`group/project`)
+    /// * `base_url` - self-hosted GitLab URL
+    ///
+    pub fn custom(project: &str, base_url: Option<String>) -> Self {
+        Self {
+            base_url: base_url
+                .unwrap_or_else(|| DEFAULT_GITLAB_URL.to_string())
+                .trim_end_matches('/')
+                .to_string(),
+            project: project.to_string(),
+            tag_filter: None,
+        }
+    }
+
+    /// Consider only the releases with tags that matched the filter, the highest stable version of the latest
+    /// 100 releases is used. The release version is the version extracted from the tag (`cli-v1.2.3` -> `1.2.3`).
+    ///
+    /// # Arguments
+    ///
+    /// * `tag_filter` - tag prefix or regex
+    pub fn with_tag_filter(mut self, tag_filter: TagFilter) -> Self {
+        self.tag_filter = Some(tag_filter);
+        self
+    }
+
+    /// Select the release and the release version
+    fn select(&self, releases: Vec<ReleasesResponse>) -> Option<(String, ReleasesResponse)> {
+        match &self.tag_filter {
+            Some(filter) => releases
+                .into_iter()
+                .filter_map(|release| {
+                    filter
+                        .version(&release.tag_name)
+                        .map(|version| (version, release))
+                })
+                .filter(|(version, _)| version.pre.is_empty())
+                .max_by(|(a, _), (b, _)| a.cmp(b))
+                .map(|(version, release)| (version.to_string(), release)),
+            // gitlab return the releases sorted by release date, and the request is limited to 1 item response.
+            None => releases
+                .into_iter()
+                .next()
+                .map(|release| (release.tag_name.clone(), release)),
+        }
+    }
+
+    /// GitLab accepts the numeric project id as is, and the project path only when it URL-encoded.
+    fn project_id(&self, client: &mut Easy) -> String {
+        if self.project.chars().all(|c| c.is_ascii_digit()) {
+            return self.project.clone();
+        }
+        client.url_encode(self.project.as_bytes())
+    }
+}
+
+impl data::Vendor for GitLabVendor {
+    fn id(&self) -> String {
+        match &self.tag_filter {
+            Some(filter) => format!(
+                "{}/api/v4/projects/{}#{}",
+                self.base_url,
+                self.project,
+                filter.as_str()
+            ),
+            None => format!("{}/api/v4/projects/{}", self.base_url, self.project),
+        }
+    }
+
+    /// Get latest release version
+    fn get(&self, client: MutexGuard<Easy>) -> Result<data::Release> {
+        let mut client = client;
+
+        let per_page = if self.tag_filter.is_some() {
+            FILTERED_RELEASES_PER_PAGE
+        } else {
+            1
+        };
+        let url = format!(
+            "{}/api/v4/projects/{}/releases?per_page={}",
+            self.base_url,
+            self.project_id(&mut client),
+            per_page
+        );
+
+        let response = http::get(&mut client, &url, List::new())?.error_for_status()?;
+        let response: Vec<ReleasesResponse> = serde_json::from_slice(&response.body)?;
+
+        let (version, release_details) = match self.select(response) {
+            Some(r) => r,
+            None => return Err(anyhow!("releases not found")),
+        };
+
+        let download_releases = release_details
+            .assets
+            .links
+            .iter()
+            .map(|link| link.url.to_string())
+            .collect::<Vec<_>>();
+
+        Ok(data::Release {
+            version,
+            downloads_releases: download_releases,
+            download_link: None,
+            install_command: None,
+            source: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod vendor_gitlab_test {
+    use crate::data::Vendor;
+
+    use super::{Easy, GitLabVendor};
+    use crate::vendors::TagFilter;
+    use insta::assert_debug_snapshot;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn can_get_release_details() {
+        let url = &mockito::server_url();
+
+        let gitlab = GitLabVendor::custom("group/project", Some(url.to_string()));
+
+        let data = r#"[
+            {
+                "tag_name": "v0.1.6",
+                "assets": {
+                    "count": 3,
+                    "sources": [
+                        {
+                            "format": "zip",
+                            "url": "https://gitlab.com/group/project/-/archive/v0.1.6/project-v0.1.6.zip"
+                        }
+                    ],
+                    "links": [
+                        {
+                            "id": 1,
+                            "name": "linux",
+                            "url": "https://gitlab.com/foo",
+                            "link_type": "package"
+                        },
+                        {
+                            "id": 2,
+                            "name": "macos",
+                            "url": "https://gitlab.com/bar",
+                            "link_type": "package"
+                        }
+                    ]
+                }
+            }
+        ]
+        "#;
+
+        let _m = mockito::mock(
+            "GET",
+            "/api/v4/projects/group%2Fproject/releases?per_page=1",
+        )
+        .with_body(data)
+        .with_status(200)
+        .create();
+
+        let easy = Easy::new();
+        assert_debug_snapshot!(gitlab.get(Arc::new(Mutex::new(easy)).lock().unwrap()));
+    }
+
+    #[test]
+    fn can_get_release_details_by_project_id() {
+        let url = &mockito::server_url();
+
+        let gitlab = GitLabVendor::custom("42", Some(url.to_string()));
+
+        let _m = mockito::mock("GET", "/api/v4/projects/42/releases?per_page=1")
+            .with_body(r#"[{"tag_name": "1.0.0", "assets": {"links": []}}]"#)
+            .with_status(200)
+            .create();
+
+        let easy = Easy::new();
+        assert_debug_snapshot!(gitlab.get(Arc::new(Mutex::new(easy)).lock().unwrap()));
+    }
+
+    #[test]
+    fn can_get_release_details_without_releases() {
+        let url = &mockito::server_url();
+
+        let gitlab = GitLabVendor::custom("group/project", Some(url.to_string()));
+
+        let _m = mockito::mock(
+            "GET",
+            "/api/v4/projects/group%2Fproject/releases?per_page=1",
+        )
+        .with_body("[]")
+        .with_status(200)
+        .create();
+
+        let easy = Easy::new();
+        assert_debug_snapshot!(gitlab.get(Arc::new(Mutex::new(easy)).lock().unwrap()));
+    }
+
+    #[test]
+    fn can_get_release_details_with_not_found_project() {
+        let url = &mockito::server_url();
+
+        let gitlab = GitLabVendor::custom("group/project", Some(url.to_string()));
+
+        let _m = mockito::mock(
+            "GET",
+            "/api/v4/projects/group%2Fproject/releases?per_page=1",
+        )
+        .with_body(r#"{"message":"404 Project Not Found"}"#)
+        .with_status(404)
+        .create();
+
+        let easy = Easy::new();
+        assert_debug_snapshot!(gitlab.get(Arc::new(Mutex::new(easy)).lock().unwrap()));
+    }
+
+    #[test]
+    fn can_filter_monorepo_tags() {
+        let url = &mockito::server_url();
+
+        let gitlab = GitLabVendor::custom("42", Some(url.to_string()))
+            .with_tag_filter(TagFilter::prefix("agent/"));
+
+        let _m = mockito::mock("GET", "/api/v4/projects/42/releases?per_page=100")
+            .with_body(
+                r#"[
+                    {"tag_name": "cli-v3.0.0", "assets": {"links": []}},
+                    {"tag_name": "agent/1.5.0-rc.1", "assets": {"links": []}},
+                    {"tag_name": "agent/1.4.0", "assets": {"links": [{"url": "https://gitlab.com/agent"}]}}
+                ]"#,
+            )
+            .with_status(200)
+            .create();
+
+        let easy = Easy::new();
+        assert_debug_snapshot!(gitlab.get(Arc::new(Mutex::new(easy)).lock().unwrap()));
+    }
+}
@@ -0,0 +1,101 @@
+use crate::data::{CacheValidators, CachedRelease};
+use anyhow::anyhow;
+use anyhow::Result;
+use curl::easy::{Easy, List};
+
+/// Raw HTTP response returned from the vendor endpoint
+pub struct Response {
+    pub status: u32,
+    /// Response headers, the header names are lowercase
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    /// Return an error when the response status is not 2xx
+    pub fn error_for_status(self) -> Result<Self> {
+        if (200..300).contains(&self.status) {
+            return Ok(self);
+        }
+        Err(anyhow!(
+            "unexpected response status: {}. body: {}",
+            self.status,
+            String::from_utf8_lossy(&self.body)
+        ))
+    }
+
+    /// Return true when the response is `304 Not Modified`
+    pub fn is_not_modified(&self) -> bool {
+        self.status == 304
+    }
+
+    /// Response validators for the next conditional request
+    pub fn validators(&self) -> CacheValidators {
+        CacheValidators {
+            etag: self.header("etag").map(|v| v.to_string()),
+            last_modified: self.header("last-modified").map(|v| v.to_string()),
+        }
+    }
+
+    /// Get response header value by name (case insensitive)
+    pub fn header(&self, name: &str) -> Option<&str> {
+        let name = name.to_lowercase();
+        self.headers
+            .iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Append the conditional request headers of the cached release
+pub fn append_conditional_headers(
+    headers: &mut List,
+    cached: Option<&CachedRelease>,
+) -> Result<()> {
+    let validators = match cached {
+        Some(cached) => &cached.validators,
+        None => return Ok(()),
+    };
+    if let Some(etag) = &validators.etag {
+        headers.append(format!("if-none-match: {}", etag).as_str())?;
+    }
+    if let Some(last_modified) = &validators.last_modified {
+        headers.append(format!("if-modified-since: {}", last_modified).as_str())?;
+    }
+    Ok(())
+}
+
+/// Perform GET request with the given headers.
+///
+/// The headers are always set (even when empty) to make sure that headers from a previous vendor request
+/// are not sent again with the same client.
+pub fn get(client: &mut Easy, url: &str, headers: List) -> Result<Response> {
+    let mut body = Vec::new();
+    let mut response_headers = Vec::new();
+    client.url(url)?;
+    client.http_headers(headers)?;
+    {
+        let mut transfer = client.transfer();
+        transfer.header_function(|header| {
+            let header = String::from_utf8_lossy(header);
+            // a new status line means a new response (redirect or 100-continue), keep only the last one headers.
+            if header.starts_with("HTTP/") {
+                response_headers.clear();
+            } else if let Some((name, value)) = header.split_once(':') {
+                response_headers.push((name.trim().to_lowercase(), value.trim().to_string()));
+            }
+            true
+        })?;
+        transfer.write_function(|data| {
+            body.extend_from_slice(data);
+            Ok(data.len())
+        })?;
+        transfer.perform()?;
+    }
+
+    Ok(Response {
+        status: client.response_code()?,
+        headers: response_headers,
+        body,
+    })
+}
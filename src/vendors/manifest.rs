@@ -0,0 +1,344 @@
+use crate::data;
+use crate::vendors::http;
+use anyhow::anyhow;
+use anyhow::Result;
+use curl::easy::{Easy, List};
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::sync::MutexGuard;
+
+/// Supported manifest schema version
+const MANIFEST_SCHEMA_VERSION: u32 = 1;
+
+/// Manifest file format
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ManifestFormat {
+    Json,
+    Toml,
+    Yaml,
+}
+
+impl ManifestFormat {
+    /// Detect the format from the file extension
+    fn from_path(path: &str) -> Option<Self> {
+        let path = path.split(['?', '#']).next().unwrap_or(path).to_lowercase();
+        if path.ends_with(".json") {
+            Some(Self::Json)
+        } else if path.ends_with(".toml") {
+            Some(Self::Toml)
+        } else if path.ends_with(".yaml") || path.ends_with(".yml") {
+            Some(Self::Yaml)
+        } else {
+            None
+        }
+    }
+
+    fn parse(self, content: &[u8]) -> Result<ManifestFile> {
+        Ok(match self {
+            Self::Json => serde_json::from_slice(content)?,
+            Self::Toml => toml::from_slice(content)?,
+            Self::Yaml => serde_yaml::from_slice(content)?,
+        })
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+struct ManifestFile {
+    schema_version: u32,
+    releases: Vec<ManifestRelease>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+struct ManifestRelease {
+    version: String,
+    #[serde(default)]
+    install_command: Option<String>,
+    #[serde(default)]
+    assets: Vec<ManifestAsset>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+struct ManifestAsset {
+    url: String,
+    #[serde(default)]
+    os: Option<String>,
+    #[serde(default)]
+    arch: Option<String>,
+}
+
+impl ManifestAsset {
+    /// Asset without `os`/`arch` match any platform
+    fn is_match(&self, os: &str, arch: &str) -> bool {
+        let os_match = match &self.os {
+            Some(asset_os) => Self::aliases(os).contains(&asset_os.to_lowercase().as_str()),
+            None => true,
+        };
+        let arch_match = match &self.arch {
+            Some(asset_arch) => Self::aliases(arch).contains(&asset_arch.to_lowercase().as_str()),
+            None => true,
+        };
+        os_match && arch_match
+    }
+
+    fn aliases(name: &str) -> Vec<&str> {
+        match name {
+            "macos" => vec!["macos", "darwin", "osx"],
+            "windows" => vec!["windows", "win"],
+            "x86_64" => vec!["x86_64", "amd64", "x64"],
+            "aarch64" => vec!["aarch64", "arm64"],
+            "x86" => vec!["x86", "i386", "i686"],
+            _ => vec![name],
+        }
+    }
+}
+
+/// Static manifest vendor
+///
+/// Read the releases from a manifest file served by any static server (or a local/shared drive via `file://`).
+/// The manifest describe each release assets per platform, so the download link of the running platform
+/// is selected explicitly.
+///
+/// ## Manifest example (JSON, TOML and YAML are supported)
+/// ```toml
+/// schema_version = 1
+///
+/// [[releases]]
+/// version = "1.2.0"
+///
+/// [[releases.assets]]
+/// os = "linux"
+/// arch = "x86_64"
+/// url = "https://downloads.example.com/app-1.2.0-linux-x86_64.tar.gz"
+///
+/// [[releases.assets]]
+/// os = "macos"
+/// arch = "aarch64"
+/// url = "https://downloads.example.com/app-1.2.0-macos-aarch64.tar.gz"
+/// ```
+///
+/// ## Usage Example
+/// ```
+/// use anyhow::Result;
+/// use upversion::vendors::ManifestVendor;
+/// use upversion::CheckVersion;
+///
+/// fn main() -> Result<()> {
+///     let manifest = Box::new(ManifestVendor::new("https://downloads.example.com/releases.toml"));
+///     let timeout = 2; // in seconds
+///     let version_context = CheckVersion::new("app-name", manifest, timeout)?;
+///
+///     // run command execute upversion check in the background and finish immediately.
+///     version_context.run("0.0.1")?;
+///
+///     // your program logic here
+///
+///     // wait until the check is done, up to the given timeout
+///     version_context.wait(std::time::Duration::from_secs(3));
+///
+///     // at the end of your program, you can call printstd to print to the STDOUT a alert information for a new version which released
+///     version_context.printstd();
+///     Ok(())
+/// }
+/// ```
+pub struct ManifestVendor {
+    url: String,
+    format: Option<ManifestFormat>,
+}
+
+impl ManifestVendor {
+    /// create manifest instance, the format is detected from the file extension
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - manifest HTTP URL or `file://` path
+    pub fn new(url: &str) -> Self {
+        Self::custom(url, None)
+    }
+
+    /// create manifest instance
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - manifest HTTP URL or `file://` path
+    /// * `format` - manifest format, when not set the format is detected from the file extension
+    ///
+    pub fn custom(url: &str, format: Option<ManifestFormat>) -> Self {
+        Self {
+            url: url.to_string(),
+            format,
+        }
+    }
+
+    fn content(&self, client: &mut Easy) -> Result<Vec<u8>> {
+        if let Some(path) = self.url.strip_prefix("file://") {
+            return Ok(std::fs::read(path)?);
+        }
+        Ok(http::get(client, &self.url, List::new())?
+            .error_for_status()?
+            .body)
+    }
+
+    fn release(manifest: ManifestFile, os: &str, arch: &str) -> Result<data::Release> {
+        if manifest.schema_version != MANIFEST_SCHEMA_VERSION {
+            return Err(anyhow!(
+                "unsupported manifest schema version: {}",
+                manifest.schema_version
+            ));
+        }
+
+        let release = manifest
+            .releases
+            .into_iter()
+            .filter_map(|release| {
+                semver::Version::parse(release.version.trim_start_matches('v'))
+                    .ok()
+                    .map(|version| (version, release))
+            })
+            .max_by(|(a, _), (b, _)| a.cmp(b));
+
+        let release = match release {
+            Some((_, release)) => release,
+            None => return Err(anyhow!("releases not found")),
+        };
+
+        // only the running platform assets are returned, to avoid falling back to download link substring matching.
+        let platform_assets = release
+            .assets
+            .into_iter()
+            .filter(|asset| asset.is_match(os, arch))
+            .map(|asset| asset.url)
+            .collect::<Vec<_>>();
+
+        Ok(data::Release {
+            version: release.version,
+            download_link: platform_assets.first().cloned(),
+            downloads_releases: platform_assets,
+            install_command: release.install_command,
+            source: None,
+        })
+    }
+}
+
+impl data::Vendor for ManifestVendor {
+    fn id(&self) -> String {
+        self.url.clone()
+    }
+
+    /// Get the newest release from the manifest
+    fn get(&self, client: MutexGuard<Easy>) -> Result<data::Release> {
+        let mut client = client;
+
+        let format = match self.format.or_else(|| ManifestFormat::from_path(&self.url)) {
+            Some(f) => f,
+            None => return Err(anyhow!("could not detect manifest format: {}", self.url)),
+        };
+
+        let manifest = format.parse(&self.content(&mut client)?)?;
+        Self::release(manifest, env::consts::OS, env::consts::ARCH)
+    }
+}
+
+#[cfg(test)]
+mod vendor_manifest_test {
+    use crate::data::Vendor;
+
+    use super::{Easy, ManifestFormat, ManifestVendor};
+    use insta::assert_debug_snapshot;
+    use std::sync::{Arc, Mutex};
+
+    const MANIFEST_TOML: &str = r#"
+schema_version = 1
+
+[[releases]]
+version = "1.1.0"
+
+[[releases.assets]]
+os = "linux"
+arch = "x86_64"
+url = "https://foo.test/app-1.1.0-linux"
+
+[[releases]]
+version = "1.2.0"
+
+[[releases.assets]]
+os = "linux"
+arch = "amd64"
+url = "https://foo.test/app-1.2.0-linux"
+
+[[releases.assets]]
+os = "darwin"
+arch = "arm64"
+url = "https://foo.test/app-1.2.0-macos"
+"#;
+
+    #[test]
+    fn can_select_platform_asset() {
+        let manifest = ManifestFormat::Toml
+            .parse(MANIFEST_TOML.as_bytes())
+            .unwrap();
+        assert_debug_snapshot!(ManifestVendor::release(manifest, "macos", "aarch64"));
+    }
+
+    #[test]
+    fn can_get_release_details_from_file() {
+        let dir =
+            std::env::temp_dir().join(format!("upversion-manifest-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("manifest.yaml");
+        std::fs::write(
+            &path,
+            r#"
+schema_version: 1
+releases:
+  - version: 1.0.0
+    install_command: brew upgrade app
+    assets:
+      - url: https://foo.test/app-1.0.0.tar.gz
+"#,
+        )
+        .unwrap();
+
+        let manifest = ManifestVendor::new(format!("file://{}", path.display()).as_str());
+
+        let easy = Easy::new();
+        assert_debug_snapshot!(manifest.get(Arc::new(Mutex::new(easy)).lock().unwrap()));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn can_get_release_details_from_url() {
+        let url = &mockito::server_url();
+
+        let data = r#"
+        {
+            "schema_version": 1,
+            "releases": [
+                {
+                    "version": "2.0.0",
+                    "assets": [{ "url": "https://foo.test/app-2.0.0.zip" }]
+                }
+            ]
+        }"#;
+
+        let _m = mockito::mock("GET", "/releases")
+            .with_body(data)
+            .with_status(200)
+            .create();
+
+        let manifest = ManifestVendor::custom(
+            format!("{}/releases", url).as_str(),
+            Some(ManifestFormat::Json),
+        );
+
+        let easy = Easy::new();
+        assert_debug_snapshot!(manifest.get(Arc::new(Mutex::new(easy)).lock().unwrap()));
+    }
+
+    #[test]
+    fn can_get_release_details_with_unsupported_schema() {
+        let manifest = ManifestFormat::Json
+            .parse(r#"{"schema_version": 2, "releases": []}"#.as_bytes())
+            .unwrap();
+        assert_debug_snapshot!(ManifestVendor::release(manifest, "linux", "x86_64"));
+    }
+}
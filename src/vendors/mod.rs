@@ -1,6 +1,31 @@
 //! List of supported vendors
+mod aggregate;
 mod api;
+mod appcast;
+mod crates;
+mod feed;
+mod git;
+mod gitea;
 mod github;
+mod gitlab;
+mod http;
+mod manifest;
+mod npm;
+mod oci;
+mod pypi;
+mod tag;
 
+pub use self::aggregate::AggregateVendor;
 pub use self::api::{Api, DeserializeResponse};
+pub use self::appcast::AppcastVendor;
+pub use self::crates::CratesIoVendor;
+pub use self::feed::FeedVendor;
+pub use self::git::GitTagsVendor;
+pub use self::gitea::GiteaVendor;
 pub use self::github::GitHubVendor;
+pub use self::gitlab::GitLabVendor;
+pub use self::manifest::{ManifestFormat, ManifestVendor};
+pub use self::npm::NpmVendor;
+pub use self::oci::OciVendor;
+pub use self::pypi::PyPiVendor;
+pub use self::tag::TagFilter;
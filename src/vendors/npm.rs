@@ -0,0 +1,218 @@
+use crate::data;
+use crate::vendors::http;
+use anyhow::anyhow;
+use anyhow::Result;
+use curl::easy::{Easy, List};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::MutexGuard;
+
+/// Default npm registry URL
+const DEFAULT_NPM_REGISTRY_URL: &str = "https://registry.npmjs.org";
+/// Default dist tag
+const DEFAULT_NPM_TAG: &str = "latest";
+
+#[derive(Deserialize, Serialize, Debug)]
+struct PackumentResponse {
+    #[serde(rename = "dist-tags")]
+    dist_tags: HashMap<String, String>,
+}
+
+/// npm vendor
+///
+/// Check if there is a new version published to npm registry under the given dist tag.
+/// The notification suggest to run `npm install -g <package>`.
+///
+/// ## Usage Example
+/// ```
+/// use anyhow::Result;
+/// use upversion::vendors::NpmVendor;
+/// use upversion::CheckVersion;
+///
+/// fn main() -> Result<()> {
+///     let npm = Box::new(NpmVendor::new("@scope/package"));
+///     let timeout = 2; // in seconds
+///     let version_context = CheckVersion::new("app-name", npm, timeout)?;
+///
+///     // run command execute upversion check in the background and finish immediately.
+///     version_context.run("0.0.1")?;
+///
+///     // your program logic here
+///
+///     // wait until the check is done, up to the given timeout
+///     version_context.wait(std::time::Duration::from_secs(3));
+///
+///     // at the end of your program, you can call printstd to print to the STDOUT a alert information for a new version which released
+///     version_context.printstd();
+///     Ok(())
+/// }
+/// ```
+pub struct NpmVendor {
+    registry_url: String,
+    package: String,
+    tag: String,
+    token: Option<String>,
+}
+
+impl NpmVendor {
+    /// create npm instance which follow the `latest` tag of the public registry
+    ///
+    /// # Arguments
+    ///
+    /// * `package` - package name, scoped packages are supported (`@scope/package`)
+    pub fn new(package: &str) -> Self {
+        Self::custom(package, None, None, None)
+    }
+
+    /// create npm instance
+    ///
+    /// # Arguments
+    ///
+    /// * `package` - package name, scoped packages are supported (`@scope/package`)
+    /// * `tag` - dist tag to follow (`latest`, `next`, ...)
+    /// * `registry_url` - private registry URL
+    /// * `token` - registry bearer token
+    ///
+    pub fn custom(
+        package: &str,
+        tag: Option<String>,
+        registry_url: Option<String>,
+        token: Option<String>,
+    ) -> Self {
+        Self {
+            registry_url: registry_url
+                .unwrap_or_else(|| DEFAULT_NPM_REGISTRY_URL.to_string())
+                .trim_end_matches('/')
+                .to_string(),
+            package: package.to_string(),
+            tag: tag.unwrap_or_else(|| DEFAULT_NPM_TAG.to_string()),
+            token,
+        }
+    }
+
+    /// The registry expect the scope separator to be encoded (`@scope%2Fpackage`)
+    fn package_path(&self) -> String {
+        self.package.replace('/', "%2F")
+    }
+
+    fn install_command(&self) -> String {
+        if self.tag == DEFAULT_NPM_TAG {
+            return format!("npm install -g {}", self.package);
+        }
+        format!("npm install -g {}@{}", self.package, self.tag)
+    }
+
+    fn headers(&self) -> Result<List> {
+        let mut headers = List::new();
+        // abbreviated metadata, dist tags are included and the response is much smaller.
+        headers.append("accept: application/vnd.npm.install-v1+json")?;
+        if let Some(token) = &self.token {
+            headers.append(format!("authorization: Bearer {}", token).as_str())?;
+        }
+        Ok(headers)
+    }
+}
+
+impl data::Vendor for NpmVendor {
+    fn id(&self) -> String {
+        format!("{}/{}@{}", self.registry_url, self.package, self.tag)
+    }
+
+    /// Get the version of the configured dist tag
+    fn get(&self, client: MutexGuard<Easy>) -> Result<data::Release> {
+        let mut client = client;
+
+        let url = format!("{}/{}", self.registry_url, self.package_path());
+
+        let response = http::get(&mut client, &url, self.headers()?)?.error_for_status()?;
+        let response: PackumentResponse = serde_json::from_slice(&response.body)?;
+
+        match response.dist_tags.get(&self.tag) {
+            Some(version) => Ok(data::Release {
+                version: version.to_string(),
+                downloads_releases: vec![],
+                download_link: None,
+                install_command: Some(self.install_command()),
+                source: None,
+            }),
+            None => Err(anyhow!("dist tag: {} not found", self.tag)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod vendor_npm_test {
+    use crate::data::Vendor;
+
+    use super::{Easy, NpmVendor};
+    use insta::assert_debug_snapshot;
+    use std::sync::{Arc, Mutex};
+
+    const PACKUMENT: &str = r#"
+    {
+        "name": "@scope/package",
+        "dist-tags": {
+            "latest": "1.2.0",
+            "next": "1.3.0-rc.1"
+        },
+        "versions": {}
+    }
+    "#;
+
+    #[test]
+    fn can_get_release_details() {
+        let url = &mockito::server_url();
+
+        let npm = NpmVendor::custom("@scope/package", None, Some(url.to_string()), None);
+
+        let _m = mockito::mock("GET", "/@scope%2Fpackage")
+            .match_header("accept", "application/vnd.npm.install-v1+json")
+            .with_body(PACKUMENT)
+            .with_status(200)
+            .create();
+
+        let easy = Easy::new();
+        assert_debug_snapshot!(npm.get(Arc::new(Mutex::new(easy)).lock().unwrap()));
+    }
+
+    #[test]
+    fn can_get_release_details_with_tag_and_token() {
+        let url = &mockito::server_url();
+
+        let npm = NpmVendor::custom(
+            "@scope/package",
+            Some("next".to_string()),
+            Some(url.to_string()),
+            Some("secret".to_string()),
+        );
+
+        let _m = mockito::mock("GET", "/@scope%2Fpackage")
+            .match_header("authorization", "Bearer secret")
+            .with_body(PACKUMENT)
+            .with_status(200)
+            .create();
+
+        let easy = Easy::new();
+        assert_debug_snapshot!(npm.get(Arc::new(Mutex::new(easy)).lock().unwrap()));
+    }
+
+    #[test]
+    fn can_get_release_details_without_tag() {
+        let url = &mockito::server_url();
+
+        let npm = NpmVendor::custom(
+            "@scope/package",
+            Some("beta".to_string()),
+            Some(url.to_string()),
+            None,
+        );
+
+        let _m = mockito::mock("GET", "/@scope%2Fpackage")
+            .with_body(PACKUMENT)
+            .with_status(200)
+            .create();
+
+        let easy = Easy::new();
+        assert_debug_snapshot!(npm.get(Arc::new(Mutex::new(easy)).lock().unwrap()));
+    }
+}
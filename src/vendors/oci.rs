@@ -0,0 +1,429 @@
+use crate::data;
+use crate::vendors::{http, TagFilter};
+use anyhow::anyhow;
+use anyhow::Result;
+use curl::easy::{Easy, List};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::MutexGuard;
+
+/// Docker Hub registry API URL
+const DOCKER_HUB_URL: &str = "https://registry-1.docker.io";
+/// Max tags pages to follow
+const MAX_TAGS_PAGES: usize = 20;
+
+#[derive(Deserialize, Serialize, Debug)]
+struct TagsResponse {
+    #[serde(default)]
+    tags: Option<Vec<String>>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+struct TokenResponse {
+    #[serde(default)]
+    token: Option<String>,
+    #[serde(default)]
+    access_token: Option<String>,
+}
+
+/// Container registry vendor
+///
+/// Check if there is a newer image tag in OCI distribution registry (Docker Hub, GHCR, ...).
+/// The highest semver tag is used and the notification suggest to pull the new image.
+///
+/// ## Usage Example
+/// ```
+/// use anyhow::Result;
+/// use upversion::vendors::OciVendor;
+/// use upversion::CheckVersion;
+///
+/// fn main() -> Result<()> {
+///     let oci = Box::new(OciVendor::new("ghcr.io", "owner/image"));
+///     let timeout = 2; // in seconds
+///     let version_context = CheckVersion::new("app-name", oci, timeout)?;
+///
+///     // run command execute upversion check in the background and finish immediately.
+///     version_context.run("0.0.1")?;
+///
+///     // your program logic here
+///
+///     // wait until the check is done, up to the given timeout
+///     version_context.wait(std::time::Duration::from_secs(3));
+///
+///     // at the end of your program, you can call printstd to print to the STDOUT a alert information for a new version which released
+///     version_context.printstd();
+///     Ok(())
+/// }
+/// ```
+pub struct OciVendor {
+    base_url: String,
+    name: String,
+    image: String,
+    tag_filter: Option<TagFilter>,
+}
+
+impl OciVendor {
+    /// create container registry instance
+    ///
+    /// # Arguments
+    ///
+    /// * `registry` - registry host (`ghcr.io`, `quay.io`, ...)
+    /// * `name` - image repository name
+    pub fn new(registry: &str, name: &str) -> Self {
+        Self::custom(name, format!("https://{}", registry).as_str(), None)
+    }
+
+    /// create Docker Hub instance
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - image name, official images can be used without the `library/` prefix
+    pub fn docker_hub(name: &str) -> Self {
+        let repository = if name.contains('/') {
+            name.to_string()
+        } else {
+            format!("library/{}", name)
+        };
+        Self::custom(&repository, DOCKER_HUB_URL, Some(name.to_string()))
+    }
+
+    /// create container registry instance
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - image repository name
+    /// * `base_url` - registry API URL
+    /// * `image` - image reference without the tag, by default the registry host and the repository name
+    ///
+    pub fn custom(name: &str, base_url: &str, image: Option<String>) -> Self {
+        let base_url = base_url.trim_end_matches('/').to_string();
+        let image = image.unwrap_or_else(|| {
+            let host = base_url.split("://").last().unwrap_or(&base_url);
+            format!("{}/{}", host, name)
+        });
+        Self {
+            base_url,
+            name: name.to_string(),
+            image,
+            tag_filter: None,
+        }
+    }
+
+    /// Consider only the tags that matched the filter. The release version is the version extracted from the
+    /// tag (`cli-v1.2.3` -> `1.2.3`), and the pull command uses the tag.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag_filter` - tag prefix or regex
+    pub fn with_tag_filter(mut self, tag_filter: TagFilter) -> Self {
+        self.tag_filter = Some(tag_filter);
+        self
+    }
+
+    /// Parse `WWW-Authenticate` bearer challenge parameters (`realm`, `service`, `scope`)
+    fn parse_challenge(header: &str) -> Option<HashMap<String, String>> {
+        let (scheme, params) = header.trim().split_once(' ')?;
+        if !scheme.eq_ignore_ascii_case("bearer") {
+            return None;
+        }
+
+        let mut challenge = HashMap::new();
+        let mut rest = params.trim();
+        while let Some((key, value)) = rest.split_once('=') {
+            let key = key.trim().trim_start_matches(',').trim().to_lowercase();
+            let value = value.trim_start();
+            let (value, next) = if let Some(value) = value.strip_prefix('"') {
+                let end = value.find('"')?;
+                (&value[..end], &value[end + 1..])
+            } else {
+                let end = value.find(',').unwrap_or(value.len());
+                (&value[..end], &value[end..])
+            };
+            challenge.insert(key, value.to_string());
+            rest = next;
+        }
+        Some(challenge)
+    }
+
+    /// Request anonymous pull token from the registry authorization service
+    fn token(&self, client: &mut Easy, challenge: &str) -> Result<String> {
+        let challenge = match Self::parse_challenge(challenge) {
+            Some(c) => c,
+            None => return Err(anyhow!("unsupported authenticate challenge: {}", challenge)),
+        };
+        let realm = match challenge.get("realm") {
+            Some(r) => r,
+            None => return Err(anyhow!("authenticate challenge realm not found")),
+        };
+
+        let scope = challenge
+            .get("scope")
+            .cloned()
+            .unwrap_or_else(|| format!("repository:{}:pull", self.name));
+        let separator = if realm.contains('?') { '&' } else { '?' };
+        let mut url = format!(
+            "{}{}scope={}",
+            realm,
+            separator,
+            client.url_encode(scope.as_bytes())
+        );
+        if let Some(service) = challenge.get("service") {
+            url.push_str(format!("&service={}", client.url_encode(service.as_bytes())).as_str());
+        }
+
+        let response = http::get(client, &url, List::new())?.error_for_status()?;
+        let response: TokenResponse = serde_json::from_slice(&response.body)?;
+        match response.token.or(response.access_token) {
+            Some(token) => Ok(token),
+            None => Err(anyhow!("registry token not found")),
+        }
+    }
+
+    fn headers(token: Option<&String>) -> Result<List> {
+        let mut headers = List::new();
+        headers.append("accept: application/json")?;
+        if let Some(token) = token {
+            headers.append(format!("authorization: Bearer {}", token).as_str())?;
+        }
+        Ok(headers)
+    }
+
+    /// Get the next tags page URL from `Link` header (`</v2/<name>/tags/list?last=..>; rel="next"`)
+    fn next_page(&self, response: &http::Response) -> Option<String> {
+        let link = response.header("link")?;
+        if !link.contains("rel=\"next\"") {
+            return None;
+        }
+        let link = link.split('<').nth(1)?.split('>').next()?;
+        if link.starts_with('/') {
+            return Some(format!("{}{}", self.base_url, link));
+        }
+        Some(link.to_string())
+    }
+
+    /// List all the repository tags
+    fn tags(&self, client: &mut Easy) -> Result<Vec<String>> {
+        let mut tags = vec![];
+        let mut token = None;
+        let mut url = Some(format!("{}/v2/{}/tags/list", self.base_url, self.name));
+
+        for _ in 0..MAX_TAGS_PAGES {
+            let page_url = match url {
+                Some(u) => u,
+                None => break,
+            };
+
+            let mut response = http::get(client, &page_url, Self::headers(token.as_ref())?)?;
+            if response.status == 401 && token.is_none() {
+                let challenge = match response.header("www-authenticate") {
+                    Some(c) => c.to_string(),
+                    None => return Err(anyhow!("registry authenticate challenge not found")),
+                };
+                token = Some(self.token(client, &challenge)?);
+                response = http::get(client, &page_url, Self::headers(token.as_ref())?)?;
+            }
+
+            let response = response.error_for_status()?;
+            url = self.next_page(&response);
+
+            let page: TagsResponse = serde_json::from_slice(&response.body)?;
+            tags.extend(page.tags.unwrap_or_default());
+        }
+
+        // the highest tag may be on the pages which were not fetched
+        if url.is_some() {
+            return Err(anyhow!(
+                "registry tags list exceeds {} pages",
+                MAX_TAGS_PAGES
+            ));
+        }
+        Ok(tags)
+    }
+}
+
+impl data::Vendor for OciVendor {
+    fn id(&self) -> String {
+        match &self.tag_filter {
+            Some(filter) => format!("{}/v2/{}#{}", self.base_url, self.name, filter.as_str()),
+            None => format!("{}/v2/{}", self.base_url, self.name),
+        }
+    }
+
+    /// Get the highest semver tag
+    fn get(&self, client: MutexGuard<Easy>) -> Result<data::Release> {
+        let mut client = client;
+
+        let filter = self.tag_filter.clone().unwrap_or_default();
+        let tag = self
+            .tags(&mut client)?
+            .into_iter()
+            .filter_map(|tag| filter.version(&tag).map(|version| (version, tag)))
+            .filter(|(version, _)| version.pre.is_empty())
+            .max_by(|(a, _), (b, _)| a.cmp(b));
+
+        match tag {
+            Some((version, tag)) => Ok(data::Release {
+                download_link: None,
+                install_command: Some(format!("docker pull {}:{}", self.image, tag)),
+                source: None,
+                version: match self.tag_filter {
+                    Some(_) => version.to_string(),
+                    None => tag,
+                },
+                downloads_releases: vec![],
+            }),
+            None => Err(anyhow!("semver tags not found")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod vendor_oci_test {
+    use crate::data::Vendor;
+
+    use super::{Easy, OciVendor};
+    use crate::vendors::TagFilter;
+    use insta::assert_debug_snapshot;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn can_parse_challenge() {
+        let challenge = OciVendor::parse_challenge(
+            r#"Bearer realm="https://auth.docker.io/token",service="registry.docker.io",scope="repository:library/nginx:pull""#,
+        )
+        .unwrap();
+        let mut challenge = challenge.into_iter().collect::<Vec<_>>();
+        challenge.sort();
+        assert_debug_snapshot!(challenge);
+        assert!(OciVendor::parse_challenge("Basic realm=\"registry\"").is_none());
+    }
+
+    #[test]
+    fn can_get_release_details() {
+        let url = &mockito::server_url();
+
+        let oci = OciVendor::custom("owner/image", url, None);
+
+        let _m = mockito::mock("GET", "/v2/owner/image/tags/list")
+            .with_body(r#"{"name": "owner/image", "tags": ["latest", "0.9.0", "v1.10.0", "1.2.0", "2.0.0-rc.1"]}"#)
+            .with_status(200)
+            .create();
+
+        let easy = Easy::new();
+        assert_debug_snapshot!(oci.get(Arc::new(Mutex::new(easy)).lock().unwrap()));
+    }
+
+    #[test]
+    fn can_get_release_details_with_anonymous_token() {
+        let url = &mockito::server_url();
+
+        let oci = OciVendor::custom("owner/image", url, Some("ghcr.io/owner/image".to_string()));
+
+        let _unauthorized = mockito::mock("GET", "/v2/owner/image/tags/list")
+            .match_header("authorization", mockito::Matcher::Missing)
+            .with_header(
+                "www-authenticate",
+                format!(
+                    r#"Bearer realm="{}/token",service="registry",scope="repository:owner/image:pull""#,
+                    url
+                )
+                .as_str(),
+            )
+            .with_status(401)
+            .create();
+
+        let _token = mockito::mock(
+            "GET",
+            "/token?scope=repository%3Aowner%2Fimage%3Apull&service=registry",
+        )
+        .with_body(r#"{"token": "secret"}"#)
+        .with_status(200)
+        .create();
+
+        let _first_page = mockito::mock("GET", "/v2/owner/image/tags/list")
+            .match_header("authorization", "Bearer secret")
+            .with_header(
+                "link",
+                r#"</v2/owner/image/tags/list?last=1.0.0&n=2>; rel="next""#,
+            )
+            .with_body(r#"{"name": "owner/image", "tags": ["0.1.0", "1.0.0"]}"#)
+            .with_status(200)
+            .create();
+
+        let _second_page = mockito::mock("GET", "/v2/owner/image/tags/list?last=1.0.0&n=2")
+            .match_header("authorization", "Bearer secret")
+            .with_body(r#"{"name": "owner/image", "tags": ["1.1.0"]}"#)
+            .with_status(200)
+            .create();
+
+        let easy = Easy::new();
+        assert_debug_snapshot!(oci.get(Arc::new(Mutex::new(easy)).lock().unwrap()));
+    }
+
+    #[test]
+    fn can_get_token_with_realm_query() {
+        let url = &mockito::server_url();
+
+        let oci = OciVendor::custom("owner/image", url, None);
+
+        let _token = mockito::mock(
+            "GET",
+            "/token?account=anonymous&scope=repository%3Aowner%2Fimage%3Apull",
+        )
+        .with_body(r#"{"access_token": "secret"}"#)
+        .with_status(200)
+        .create();
+
+        let challenge = format!(r#"Bearer realm="{}/token?account=anonymous""#, url);
+        let mut easy = Easy::new();
+        assert_eq!(oci.token(&mut easy, &challenge).unwrap(), "secret");
+    }
+
+    #[test]
+    fn can_get_release_details_with_too_many_pages() {
+        let url = &mockito::server_url();
+
+        let oci = OciVendor::custom("owner/paged", url, None);
+
+        let _m = mockito::mock("GET", "/v2/owner/paged/tags/list")
+            .with_header("link", r#"</v2/owner/paged/tags/list>; rel="next""#)
+            .with_body(r#"{"name": "owner/paged", "tags": ["1.0.0"]}"#)
+            .with_status(200)
+            .create();
+
+        let easy = Easy::new();
+        assert_debug_snapshot!(oci.get(Arc::new(Mutex::new(easy)).lock().unwrap()));
+    }
+
+    #[test]
+    fn can_get_release_details_with_tag_filter() {
+        let url = &mockito::server_url();
+
+        let oci =
+            OciVendor::custom("owner/tools", url, None).with_tag_filter(TagFilter::prefix("cli-"));
+
+        let _m = mockito::mock("GET", "/v2/owner/tools/tags/list")
+            .with_body(r#"{"name": "owner/tools", "tags": ["agent-3.0.0", "cli-v1.2.0", "cli-1.10.0", "cli-2.0.0-rc.1"]}"#)
+            .with_status(200)
+            .create();
+
+        let easy = Easy::new();
+        assert_debug_snapshot!(oci.get(Arc::new(Mutex::new(easy)).lock().unwrap()));
+        assert_debug_snapshot!(oci.id());
+    }
+
+    #[test]
+    fn can_get_release_details_without_semver_tags() {
+        let url = &mockito::server_url();
+
+        let oci = OciVendor::custom("owner/image", url, None);
+
+        let _m = mockito::mock("GET", "/v2/owner/image/tags/list")
+            .with_body(r#"{"name": "owner/image", "tags": ["latest", "main"]}"#)
+            .with_status(200)
+            .create();
+
+        let easy = Easy::new();
+        assert_debug_snapshot!(oci.get(Arc::new(Mutex::new(easy)).lock().unwrap()));
+    }
+}
@@ -0,0 +1,333 @@
+use crate::data;
+use crate::vendors::http;
+use anyhow::anyhow;
+use anyhow::Result;
+use curl::easy::{Easy, List};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::MutexGuard;
+
+/// Default PyPI base URL
+const DEFAULT_PYPI_URL: &str = "https://pypi.org";
+
+#[derive(Deserialize, Serialize, Debug)]
+struct ProjectResponse {
+    releases: HashMap<String, Vec<ReleaseFileResponse>>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+struct ReleaseFileResponse {
+    url: String,
+    #[serde(default)]
+    yanked: bool,
+}
+
+/// PyPI vendor
+///
+/// Check if there is a new version of a project published to PyPI (or any index that serve the PyPI JSON API).
+/// Yanked files are ignored, and the wheels/sdist of the newest version are used as the download links.
+///
+/// ## Usage Example
+/// ```
+/// use anyhow::Result;
+/// use upversion::vendors::PyPiVendor;
+/// use upversion::CheckVersion;
+///
+/// fn main() -> Result<()> {
+///     let pypi = Box::new(PyPiVendor::new("project"));
+///     let timeout = 2; // in seconds
+///     let version_context = CheckVersion::new("app-name", pypi, timeout)?;
+///
+///     // run command execute upversion check in the background and finish immediately.
+///     version_context.run("0.0.1")?;
+///
+///     // your program logic here
+///
+///     // wait until the check is done, up to the given timeout
+///     version_context.wait(std::time::Duration::from_secs(3));
+///
+///     // at the end of your program, you can call printstd to print to the STDOUT a alert information for a new version which released
+///     version_context.printstd();
+///     Ok(())
+/// }
+/// ```
+pub struct PyPiVendor {
+    base_url: String,
+    project: String,
+    prereleases: bool,
+}
+
+impl PyPiVendor {
+    /// create PyPI instance, pre-releases are ignored
+    ///
+    /// # Arguments
+    ///
+    /// * `project` - PyPI project name
+    pub fn new(project: &str) -> Self {
+        Self::custom(project, None, false)
+    }
+
+    /// create PyPI instance
+    ///
+    /// # Arguments
+    ///
+    /// * `project` - PyPI project name
+    /// * `base_url` - PyPI compatible index URL
+    /// * `prereleases` - include pre-releases (`a`, `b`, `rc` and `dev` versions)
+    ///
+    pub fn custom(project: &str, base_url: Option<String>, prereleases: bool) -> Self {
+        Self {
+            base_url: base_url
+                .unwrap_or_else(|| DEFAULT_PYPI_URL.to_string())
+                .trim_end_matches('/')
+                .to_string(),
+            project: project.to_string(),
+            prereleases,
+        }
+    }
+
+    /// Convert PEP 440 version to semver.
+    ///
+    /// Missing release segments are filled with zeros (`1.2` -> `1.2.0`), pre-releases and development releases
+    /// are converted to semver pre-release (`1.2.0rc1` -> `1.2.0-rc.1`), and post-releases to build metadata
+    /// (`1.2.0.post1` -> `1.2.0+post.1`). The suffix number is a separate identifier, so it is compared as number.
+    /// Versions with epoch or more than 3 release segments are not supported.
+    fn parse_version(version: &str) -> Option<semver::Version> {
+        let version = version.trim().to_lowercase();
+        let version = version.split('+').next()?.trim_start_matches('v');
+
+        let suffix_index = version
+            .find(|c: char| c.is_ascii_alphabetic())
+            .unwrap_or(version.len());
+        let (release, suffix) = version.split_at(suffix_index);
+        let suffix = suffix.trim_start_matches(['.', '-', '_']);
+
+        let mut release = release
+            .trim_end_matches(['.', '-', '_'])
+            .split('.')
+            .map(|segment| segment.parse::<u64>().ok())
+            .collect::<Option<Vec<_>>>()?;
+        if release.len() > 3 {
+            return None;
+        }
+        release.resize(3, 0);
+
+        let mut version = semver::Version::new(release[0], release[1], release[2]);
+        if suffix.is_empty() {
+            return Some(version);
+        }
+
+        let suffix = suffix.replace(['-', '_'], ".");
+        let number_index = suffix
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(suffix.len());
+        let (label, number) = suffix.split_at(number_index);
+        let label = label.trim_end_matches('.');
+        let number = if number.is_empty() { "0" } else { number };
+
+        match label {
+            "post" => {
+                version.build =
+                    semver::BuildMetadata::new(format!("post.{}", number).as_str()).ok()?;
+            }
+            // development releases sort before the alpha releases, the numeric `0` identifier is lower than any
+            // alphanumeric pre-release (`a.1`, `b.1`, `rc.1`)
+            "dev" => {
+                version.pre = semver::Prerelease::new(format!("0.dev.{}", number).as_str()).ok()?;
+            }
+            label => {
+                version.pre =
+                    semver::Prerelease::new(format!("{}.{}", label, number).as_str()).ok()?;
+            }
+        }
+        Some(version)
+    }
+
+    fn install_command(&self) -> String {
+        if self.base_url == DEFAULT_PYPI_URL {
+            return format!("pip install -U {}", self.project);
+        }
+        format!(
+            "pip install -U {} --index-url {}/simple",
+            self.project, self.base_url
+        )
+    }
+}
+
+impl data::Vendor for PyPiVendor {
+    fn id(&self) -> String {
+        match self.prereleases {
+            true => format!("{}/pypi/{}#prereleases", self.base_url, self.project),
+            false => format!("{}/pypi/{}", self.base_url, self.project),
+        }
+    }
+
+    /// Get newest version with non-yanked files
+    fn get(&self, client: MutexGuard<Easy>) -> Result<data::Release> {
+        let mut client = client;
+
+        let url = format!("{}/pypi/{}/json", self.base_url, self.project);
+        let mut headers = List::new();
+        headers.append("accept: application/json")?;
+
+        let response = http::get(&mut client, &url, headers)?.error_for_status()?;
+        let response: ProjectResponse = serde_json::from_slice(&response.body)?;
+
+        let release = response
+            .releases
+            .into_iter()
+            .filter_map(|(version, files)| {
+                let files = files
+                    .into_iter()
+                    .filter(|file| !file.yanked)
+                    .map(|file| file.url)
+                    .collect::<Vec<_>>();
+                if files.is_empty() {
+                    return None;
+                }
+                Self::parse_version(&version).map(|version| (version, files))
+            })
+            .filter(|(version, _)| self.prereleases || version.pre.is_empty())
+            .max_by(|(a, _), (b, _)| a.cmp(b));
+
+        match release {
+            Some((version, files)) => Ok(data::Release {
+                version: version.to_string(),
+                downloads_releases: files,
+                download_link: None,
+                install_command: Some(self.install_command()),
+                source: None,
+            }),
+            None => Err(anyhow!("project releases not found")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod vendor_pypi_test {
+    use crate::data::Vendor;
+
+    use super::{Easy, PyPiVendor};
+    use insta::assert_debug_snapshot;
+    use std::sync::{Arc, Mutex};
+
+    const PROJECT: &str = r#"
+    {
+        "info": {
+            "name": "project",
+            "version": "1.3.0rc1"
+        },
+        "releases": {
+            "1.1": [
+                {
+                    "filename": "project-1.1.tar.gz",
+                    "packagetype": "sdist",
+                    "url": "https://files.pythonhosted.org/project-1.1.tar.gz",
+                    "yanked": false
+                }
+            ],
+            "1.2.0": [
+                {
+                    "filename": "project-1.2.0-cp37-abi3-manylinux_2_17_x86_64.whl",
+                    "packagetype": "bdist_wheel",
+                    "url": "https://files.pythonhosted.org/project-1.2.0-cp37-abi3-manylinux_2_17_x86_64.whl",
+                    "yanked": true
+                }
+            ],
+            "1.3.0rc1": [
+                {
+                    "filename": "project-1.3.0rc1.tar.gz",
+                    "packagetype": "sdist",
+                    "url": "https://files.pythonhosted.org/project-1.3.0rc1.tar.gz",
+                    "yanked": false
+                }
+            ],
+            "2.0.0": []
+        }
+    }
+    "#;
+
+    #[test]
+    fn can_parse_version() {
+        assert_debug_snapshot!([
+            "1",
+            "1.2",
+            "1.2.3rc1",
+            "1.2.3.dev4",
+            "1.2.3dev",
+            "1.2.3.post1",
+            "1.2.3+local",
+            "1.2.3.4",
+            "1!2.0",
+        ]
+        .iter()
+        .map(|v| PyPiVendor::parse_version(v).map(|v| v.to_string()))
+        .collect::<Vec<_>>());
+
+        // development releases sort before the pre-releases, and the suffix numbers are compared as numbers
+        let versions = [
+            "1.2.3.post10",
+            "1.2.3.post2",
+            "1.2.3",
+            "1.2.3rc10",
+            "1.2.3rc2",
+            "1.2.3rc1",
+            "1.2.3b1",
+            "1.2.3a1",
+            "1.2.3.dev10",
+            "1.2.3.dev4",
+        ]
+        .iter()
+        .map(|v| PyPiVendor::parse_version(v).unwrap())
+        .collect::<Vec<_>>();
+        let mut sorted = versions.clone();
+        sorted.sort();
+        sorted.reverse();
+        assert_eq!(sorted, versions);
+    }
+
+    #[test]
+    fn can_get_release_details() {
+        let url = &mockito::server_url();
+
+        let pypi = PyPiVendor::custom("project", Some(url.to_string()), false);
+
+        let _m = mockito::mock("GET", "/pypi/project/json")
+            .with_body(PROJECT)
+            .with_status(200)
+            .create();
+
+        let easy = Easy::new();
+        assert_debug_snapshot!(pypi.get(Arc::new(Mutex::new(easy)).lock().unwrap()));
+    }
+
+    #[test]
+    fn can_get_release_details_with_prereleases() {
+        let url = &mockito::server_url();
+
+        let pypi = PyPiVendor::custom("project", Some(url.to_string()), true);
+
+        let _m = mockito::mock("GET", "/pypi/project/json")
+            .with_body(PROJECT)
+            .with_status(200)
+            .create();
+
+        let easy = Easy::new();
+        assert_debug_snapshot!(pypi.get(Arc::new(Mutex::new(easy)).lock().unwrap()));
+    }
+
+    #[test]
+    fn can_get_release_details_without_releases() {
+        let url = &mockito::server_url();
+
+        let pypi = PyPiVendor::custom("project", Some(url.to_string()), false);
+
+        let _m = mockito::mock("GET", "/pypi/project/json")
+            .with_body(r#"{"info": {"version": "1.0.0"}, "releases": {}}"#)
+            .with_status(200)
+            .create();
+
+        let easy = Easy::new();
+        assert_debug_snapshot!(pypi.get(Arc::new(Mutex::new(easy)).lock().unwrap()));
+    }
+}
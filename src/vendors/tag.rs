@@ -0,0 +1,125 @@
+use anyhow::anyhow;
+use anyhow::Result;
+use regex::Regex;
+
+/// Name of the regex group that captures the version
+const VERSION_GROUP: &str = "version";
+
+/// Tag filter
+///
+/// Select the tags of a single component (for monorepos that release several components from the same
+/// repository), and extract the version from the tag before semver parsing.
+///
+/// ## Usage Example
+/// ```
+/// use anyhow::Result;
+/// use upversion::vendors::{GitHubVendor, TagFilter};
+///
+/// fn main() -> Result<()> {
+///     // `cli-v1.2.3` / `cli-1.2.3` -> `1.2.3`
+///     let github = GitHubVendor::new("owner", "repo").with_tag_filter(TagFilter::prefix("cli-"));
+///     // `agent/1.4.0` -> `1.4.0`
+///     let github = GitHubVendor::new("owner", "repo")
+///         .with_tag_filter(TagFilter::regex(r"^agent/(?P<version>\d+\.\d+\.\d+)$")?);
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct TagFilter {
+    regex: Regex,
+}
+
+impl Default for TagFilter {
+    /// Match all the tags, and strip the leading `v`
+    fn default() -> Self {
+        Self::prefix("")
+    }
+}
+
+impl TagFilter {
+    /// Match the tags that start with the given prefix. The prefix and the leading `v` after it are stripped.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - tag prefix (`cli-`, `agent/`)
+    pub fn prefix(prefix: &str) -> Self {
+        let pattern = format!(r"^{}v?(?P<{}>.+)$", regex::escape(prefix), VERSION_GROUP);
+        Self {
+            regex: Regex::new(&pattern).expect("escaped prefix is a valid regex"),
+        }
+    }
+
+    /// Match the tags with the given regex. The version is taken from the `version` named group.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - regex pattern with `(?P<version>...)` group
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the pattern is invalid or without `version` group
+    pub fn regex(pattern: &str) -> Result<Self> {
+        let regex = Regex::new(pattern)?;
+        if !regex
+            .capture_names()
+            .any(|name| name == Some(VERSION_GROUP))
+        {
+            return Err(anyhow!(
+                "tag pattern must contain `{}` named group: {}",
+                VERSION_GROUP,
+                pattern
+            ));
+        }
+        Ok(Self { regex })
+    }
+
+    /// Regex pattern of the filter
+    pub fn as_str(&self) -> &str {
+        self.regex.as_str()
+    }
+
+    /// Return the tag version, or `None` when the tag is not matched or the version is not a valid semver
+    pub fn version(&self, tag: &str) -> Option<semver::Version> {
+        let version = self.regex.captures(tag)?.name(VERSION_GROUP)?.as_str();
+        semver::Version::parse(version).ok()
+    }
+}
+
+#[cfg(test)]
+mod vendor_tag_test {
+    use super::TagFilter;
+    use insta::assert_debug_snapshot;
+
+    #[test]
+    fn can_extract_version() {
+        let tags = [
+            "v1.0.0",
+            "cli-v1.2.3",
+            "cli-1.2.4",
+            "agent/1.4.0",
+            "agent/1.4.0-rc.1",
+            "agent/latest",
+        ];
+
+        let filters = [
+            TagFilter::default(),
+            TagFilter::prefix("cli-"),
+            TagFilter::regex(r"^agent/(?P<version>.+)$").unwrap(),
+        ];
+
+        assert_debug_snapshot!(filters
+            .iter()
+            .map(|filter| tags
+                .iter()
+                .filter_map(|tag| filter
+                    .version(tag)
+                    .map(|v| (tag.to_string(), v.to_string())))
+                .collect::<Vec<_>>())
+            .collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn can_not_create_regex_without_version_group() {
+        assert_debug_snapshot!(TagFilter::regex(r"^agent/(.+)$").map(|_| ()));
+    }
+}
@@ -38,7 +38,7 @@ fn main() -> Result<(), anyhow::Error> {
             create_dir_all("coverage")?;
 
             println!("=== running coverage ===");
-            cmd!("cargo", "test")
+            cmd!("cargo", "test", "--all-features")
                 .env("CARGO_INCREMENTAL", "0")
                 .env("RUSTFLAGS", "-Cinstrument-coverage")
                 .env("LLVM_PROFILE_FILE", "cargo-test-%p-%m.profraw")
@@ -97,8 +97,8 @@ fn main() -> Result<(), anyhow::Error> {
         }
         Some(("ci", _)) => {
             cmd!("cargo", "+nightly", "fmt", "--all", "--", "--check").run()?;
-            cmd!("cargo", "clippy", "--", "-D", "warnings").run()?;
-            cmd!("cargo", "test").run()?;
+            cmd!("cargo", "clippy", "--all-features", "--", "-D", "warnings").run()?;
+            cmd!("cargo", "test", "--all-features").run()?;
             Ok(())
         }
         Some(("docs", _)) => {